@@ -0,0 +1,473 @@
+//! Derive macros for `possibly_uninit`.
+//!
+//! This crate is not supposed to be used directly, activate the `derive`
+//! feature of `possibly_uninit` instead.
+
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Error, Expr, ExprLit, ExprUnary, Fields, GenericParam, Ident, Lit, Member, UnOp};
+
+/// Derives `ZeroValid` for structs and enums.
+///
+/// The impl requires all fields to be `ZeroValid`, so it's generated with
+/// a `where` clause containing all field types. Fields which are not
+/// `ZeroValid` even if all type parameters are `ZeroValid` (e.g. references)
+/// are rejected. The representation of structs doesn't matter, since padding
+/// may contain zeroes as well.
+///
+/// Enums must have `#[repr(C)]` or primitive representation and a variant
+/// with zero discriminant. Only the fields of that variant must be
+/// `ZeroValid`. The discriminants must be integer literals.
+///
+/// ```
+/// use possibly_uninit::zeroed::ZeroValid;
+///
+/// #[derive(ZeroValid)]
+/// struct Foo<T> {
+///     bar: u32,
+///     baz: T,
+///     ptr: *const u8,
+/// }
+///
+/// #[derive(ZeroValid)]
+/// #[repr(u8, align(4))]
+/// enum Kind {
+///     A = 2,
+///     B = 0,
+///     C,
+/// }
+///
+/// #[derive(ZeroValid)]
+/// #[repr(C)]
+/// enum Message<'a> {
+///     Empty(u64),
+///     Text(&'a str),
+/// }
+///
+/// let foo = Foo::<Kind>::zeroed();
+/// assert_eq!(foo.bar, 0);
+/// assert!(foo.ptr.is_null());
+/// assert!(matches!(Kind::zeroed(), Kind::B));
+/// assert!(matches!(Message::zeroed(), Message::Empty(0)));
+/// ```
+///
+/// References are not `ZeroValid`:
+///
+/// ```compile_fail,E0277
+/// use possibly_uninit::zeroed::ZeroValid;
+///
+/// #[derive(ZeroValid)]
+/// struct Foo<'a> {
+///     bar: &'a u32,
+/// }
+/// ```
+///
+/// Neither are fields of generic types that aren't `ZeroValid`:
+///
+/// ```compile_fail,E0277
+/// use possibly_uninit::zeroed::ZeroValid;
+///
+/// #[derive(ZeroValid)]
+/// struct Foo<T>(T);
+///
+/// let foo = Foo::<&u8>::zeroed();
+/// ```
+///
+/// Enums without zero discriminant are rejected:
+///
+/// ```compile_fail
+/// use possibly_uninit::zeroed::ZeroValid;
+///
+/// #[derive(ZeroValid)]
+/// #[repr(u8)]
+/// enum Foo {
+///     A = 1,
+///     B,
+/// }
+/// ```
+///
+/// As well as enums with unspecified layout:
+///
+/// ```compile_fail
+/// use possibly_uninit::zeroed::ZeroValid;
+///
+/// #[derive(ZeroValid)]
+/// enum Foo {
+///     A,
+///     B,
+/// }
+/// ```
+///
+/// Fields of the zero variant are checked:
+///
+/// ```compile_fail,E0277
+/// use possibly_uninit::zeroed::ZeroValid;
+///
+/// #[derive(ZeroValid)]
+/// #[repr(u8)]
+/// enum Foo<'a> {
+///     A(&'a u8),
+///     B(u8),
+/// }
+/// ```
+///
+/// Unions are not supported:
+///
+/// ```compile_fail
+/// use possibly_uninit::zeroed::ZeroValid;
+///
+/// #[derive(ZeroValid)]
+/// union Foo {
+///     a: u8,
+///     b: u16,
+/// }
+/// ```
+#[proc_macro_derive(ZeroValid)]
+pub fn derive_zero_valid(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    zero_valid(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn zero_valid(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => zero_variant_fields(input, data)?,
+        Data::Union(data) => return Err(Error::new_spanned(data.union_token, "ZeroValid can't be derived for unions")),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+
+    // Bounds like `&'a T: ZeroValid` are accepted in the where clause, they
+    // just never hold, so the fields are also checked assuming all type
+    // parameters are `ZeroValid`. This rejects fields which are never valid.
+    let mut check_where_clause = where_clause.clone();
+    for param in input.generics.type_params() {
+        let param = &param.ident;
+        check_where_clause.predicates.push(parse_quote!(#param: ::possibly_uninit::zeroed::ZeroValid));
+    }
+
+    let mut impl_where_clause = where_clause;
+    for ty in &types {
+        impl_where_clause.predicates.push(parse_quote!(#ty: ::possibly_uninit::zeroed::ZeroValid));
+    }
+
+    Ok(quote! {
+        const _: () = {
+            fn assert_zero_valid<T: ::possibly_uninit::zeroed::ZeroValid + ?Sized>() {}
+
+            #[allow(dead_code)]
+            fn check_fields #impl_generics() #check_where_clause {
+                #(assert_zero_valid::<#types>();)*
+            }
+        };
+
+        unsafe impl #impl_generics ::possibly_uninit::zeroed::ZeroValid for #name #ty_generics #impl_where_clause {}
+    })
+}
+
+/// Returns the fields of the variant with zero discriminant.
+fn zero_variant_fields<'a>(input: &DeriveInput, data: &'a DataEnum) -> Result<&'a Fields, Error> {
+    const REPRS: &[&str] = &["C", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"];
+
+    let mut has_repr = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if REPRS.iter().any(|repr| meta.path.is_ident(repr)) {
+                has_repr = true;
+            }
+            // Skip arguments of `align(N)` and `packed(N)`
+            if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<TokenStream2>()?;
+            }
+            Ok(())
+        })?;
+    }
+    if !has_repr {
+        return Err(Error::new_spanned(&input.ident, "ZeroValid can only be derived for enums with #[repr(C)] or primitive representation"));
+    }
+
+    let mut discriminant = 0i128;
+    for variant in &data.variants {
+        if let Some((_, expr)) = &variant.discriminant {
+            discriminant = parse_discriminant(expr)?;
+        }
+        if discriminant == 0 {
+            return Ok(&variant.fields);
+        }
+        discriminant += 1;
+    }
+    Err(Error::new_spanned(&input.ident, "ZeroValid can only be derived for enums with a variant with zero discriminant"))
+}
+
+fn parse_discriminant(expr: &Expr) -> Result<i128, Error> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) => int.base10_parse(),
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), expr, .. }) => parse_discriminant(expr).map(|value| -value),
+        Expr::Group(group) => parse_discriminant(&group.expr),
+        Expr::Paren(paren) => parse_discriminant(&paren.expr),
+        _ => Err(Error::new_spanned(expr, "ZeroValid can only be derived for enums with integer literal discriminants")),
+    }
+}
+
+/// Derives `OutProject` for structs, allowing field by field initialization.
+///
+/// For struct `Foo` this generates `FooOut` builder with a method for each
+/// field. The method takes a closure initializing the field through `Out`
+/// and must return the reference obtained by writing. There's also
+/// `write_<field>` method writing the value directly. The fields of tuple
+/// structs are named `_0`, `_1`... The methods have the same visibility as
+/// the field.
+///
+/// Each field can be written only once and `finish` returning the
+/// initialized struct is only available after all fields were written.
+/// The builder can't be constructed or moved to another state without
+/// `unsafe`, not even in the module defining the struct. Packed structs are
+/// not supported.
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use possibly_uninit::Out;
+/// use possibly_uninit::project::OutProject;
+///
+/// #[derive(OutProject)]
+/// struct Block {
+///     id: u32,
+///     data: [u8; 1 << 16],
+/// }
+///
+/// let mut block = Box::<Block>::new_uninit();
+/// let block: &mut Block = Out::<Block>::from(&mut *block)
+///     .project()
+///     .write_id(42)
+///     .data(|data| data.into_zeroed())
+///     .finish();
+/// assert_eq!(block.id, 42);
+/// assert_eq!(block.data[1000], 0);
+/// ```
+///
+/// Generic structs and nested projections are supported too:
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use possibly_uninit::Out;
+/// use possibly_uninit::project::OutProject;
+///
+/// #[derive(OutProject)]
+/// struct Generic<'a, T: Copy, const N: usize> {
+///     slice: &'a [T],
+///     array: [T; N],
+///     r#type: Option<T>,
+/// }
+///
+/// #[derive(OutProject)]
+/// struct Tuple<'a>(u8, Generic<'a, u16, 2>);
+///
+/// let mut uninit = MaybeUninit::uninit();
+/// let value = Out::<Tuple>::from(&mut uninit)
+///     .project()
+///     .write_0(1)
+///     ._1(|out| out.project().write_slice(&[4]).write_array([2, 3]).r#type(|out| out.write(None)).finish())
+///     .finish();
+/// assert_eq!(value.0, 1);
+/// assert_eq!(value.1.slice, [4]);
+/// assert_eq!(value.1.array, [2, 3]);
+/// assert_eq!(value.1.r#type, None);
+/// ```
+///
+/// Calling `finish` before writing all fields fails to compile:
+///
+/// ```compile_fail,E0599
+/// use core::mem::MaybeUninit;
+/// use possibly_uninit::Out;
+/// use possibly_uninit::project::OutProject;
+///
+/// #[derive(OutProject)]
+/// struct Foo(u8, u16);
+///
+/// let mut foo = MaybeUninit::<Foo>::uninit();
+/// Out::<Foo>::from(&mut foo).project().write_0(1).finish();
+/// ```
+///
+/// As well as writing a field twice:
+///
+/// ```compile_fail,E0599
+/// use core::mem::MaybeUninit;
+/// use possibly_uninit::Out;
+/// use possibly_uninit::project::OutProject;
+///
+/// #[derive(OutProject)]
+/// struct Foo(u8, u16);
+///
+/// let mut foo = MaybeUninit::<Foo>::uninit();
+/// Out::<Foo>::from(&mut foo).project().write_0(1).write_0(2);
+/// ```
+///
+/// The closure must return the reference to the field:
+///
+/// ```should_panic
+/// use core::mem::MaybeUninit;
+/// use possibly_uninit::Out;
+/// use possibly_uninit::project::OutProject;
+///
+/// #[derive(OutProject)]
+/// struct Foo {
+///     bar: u8,
+/// }
+///
+/// let mut foo = MaybeUninit::<Foo>::uninit();
+/// let bar = Box::leak(Box::new(42));
+/// Out::<Foo>::from(&mut foo).project().bar(move |_| bar).finish();
+/// ```
+#[proc_macro_derive(OutProject)]
+pub fn derive_out_project(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    out_project(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn out_project(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => return Err(Error::new_spanned(data.enum_token, "OutProject can only be derived for structs")),
+        Data::Union(data) => return Err(Error::new_spanned(data.union_token, "OutProject can only be derived for structs")),
+    };
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("packed") {
+                return Err(meta.error("OutProject can't be derived for packed structs"));
+            }
+            if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<TokenStream2>()?;
+            }
+            Ok(())
+        })?;
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let builder = format_ident!("{}Out", name);
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // The generic parameters of the struct without defaults and the
+    // arguments referring to them.
+    let params = input.generics.params.iter().map(|param| {
+        let mut param = param.clone();
+        match &mut param {
+            GenericParam::Type(param) => {
+                param.eq_token = None;
+                param.default = None;
+            },
+            GenericParam::Const(param) => {
+                param.eq_token = None;
+                param.default = None;
+            },
+            GenericParam::Lifetime(_) => (),
+        }
+        param
+    }).collect::<Vec<_>>();
+    let args = input.generics.params.iter().map(|param| match param {
+        GenericParam::Type(param) => { let ident = &param.ident; quote!(#ident) },
+        GenericParam::Const(param) => { let ident = &param.ident; quote!(#ident) },
+        GenericParam::Lifetime(param) => { let lifetime = &param.lifetime; quote!(#lifetime) },
+    }).collect::<Vec<_>>();
+
+    // Typestate parameter of each field.
+    let states = (0..fields.len()).map(|i| format_ident!("__State{}", i)).collect::<Vec<_>>();
+    let unset = quote!(::possibly_uninit::project::Unset);
+    let set = quote!(::possibly_uninit::project::Set);
+
+    let builder_doc = format!("Builder initializing the fields of `{}`.", name);
+    let mut methods = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        };
+        let (method, field_name) = match &field.ident {
+            Some(ident) => (ident.clone(), unraw(ident)),
+            None => (format_ident!("_{}", i), i.to_string()),
+        };
+        let write_method = format_ident!("write_{}", field_name);
+        let field_vis = &field.vis;
+        let ty = &field.ty;
+        let other_states = states.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, state)| state);
+        let before = states.iter().enumerate().map(|(j, state)| if j == i { unset.clone() } else { quote!(#state) }).collect::<Vec<_>>();
+        let after = states.iter().enumerate().map(|(j, state)| if j == i { set.clone() } else { quote!(#state) }).collect::<Vec<_>>();
+        let doc = format!("Initializes the field `{}` using the closure.\n\nThe closure must return the reference obtained by writing into the `Out`.\n\n# Panics\n\nThis method panics if the closure returns a different reference.", field_name);
+        let write_doc = format!("Writes the value into the field `{}`.", field_name);
+
+        methods.push(quote! {
+            impl<'__out, #(#params,)* #(#other_states,)*> #builder<'__out, #(#args,)* #(#before,)*> #where_clause {
+                #[doc = #doc]
+                #field_vis fn #method<__Init>(mut self, init: __Init) -> #builder<'__out, #(#args,)* #(#after,)*> where __Init: for<'__field> ::core::ops::FnOnce(::possibly_uninit::Out<'__field, #ty>) -> &'__field mut #ty {
+                    let ptr = unsafe { ::core::ptr::addr_of_mut!((*self.projection.as_mut_ptr()).#member) };
+                    // The field is properly aligned since the struct is not packed.
+                    let field = unsafe { &mut *(ptr as *mut ::core::mem::MaybeUninit<#ty>) };
+                    let initialized: *mut #ty = init(::possibly_uninit::Out::from(field));
+                    ::core::assert!(::core::ptr::eq(initialized, ptr), "the closure must return the initialized field");
+                    // The field was just written.
+                    #builder {
+                        projection: unsafe { self.projection.into_state() },
+                    }
+                }
+
+                #[doc = #write_doc]
+                #field_vis fn #write_method(self, value: #ty) -> #builder<'__out, #(#args,)* #(#after,)*> {
+                    self.#method(|out| out.write(value))
+                }
+            }
+        });
+    }
+
+    let all_set = states.iter().map(|_| &set);
+
+    Ok(quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder<'__out, #(#params,)* #(#states = #unset,)*> #where_clause {
+            projection: ::possibly_uninit::project::Projection<'__out, #name #ty_generics, (#(#states,)*)>,
+        }
+
+        #(#methods)*
+
+        impl<'__out, #(#params,)*> #builder<'__out, #(#args,)* #(#all_set,)*> #where_clause {
+            /// Returns the initialized struct.
+            #vis fn finish(self) -> &'__out mut #name #ty_generics {
+                // All fields were written.
+                unsafe {
+                    self.projection.into_assume_init()
+                }
+            }
+        }
+
+        impl<#(#params,)*> ::possibly_uninit::project::OutProject for #name #ty_generics #where_clause {
+            type Builder<'__out> = #builder<'__out, #(#args,)*> where Self: '__out;
+
+            fn project_out(out: ::possibly_uninit::Out<'_, Self>) -> Self::Builder<'_> {
+                // No field is marked as written yet.
+                #builder {
+                    projection: unsafe { ::possibly_uninit::project::Projection::new(out) },
+                }
+            }
+        }
+    })
+}
+
+/// Strips the `r#` prefix of raw identifiers.
+fn unraw(ident: &Ident) -> String {
+    let ident = ident.to_string();
+    match ident.strip_prefix("r#") {
+        Some(ident) => ident.to_owned(),
+        None => ident,
+    }
+}
@@ -0,0 +1,16 @@
+use core::mem::MaybeUninit;
+use possibly_uninit::Out;
+use possibly_uninit::project::{OutProject, Set};
+
+#[derive(OutProject)]
+struct Foo {
+    bar: String,
+}
+
+fn main() {
+    let mut foo = MaybeUninit::<Foo>::uninit();
+    let forged = FooOut::<Set> {
+        projection: possibly_uninit::project::Projection::new(Out::from(&mut foo)),
+    };
+    let _ = forged.finish().bar.len();
+}
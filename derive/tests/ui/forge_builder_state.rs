@@ -0,0 +1,17 @@
+use core::mem::MaybeUninit;
+use possibly_uninit::Out;
+use possibly_uninit::project::{OutProject, Set};
+
+#[derive(OutProject)]
+struct Foo {
+    bar: String,
+}
+
+fn main() {
+    let mut foo = MaybeUninit::<Foo>::uninit();
+    let builder = Out::<Foo>::from(&mut foo).project();
+    let forged = FooOut::<Set> {
+        projection: builder.projection,
+    };
+    let _ = forged.finish().bar.len();
+}
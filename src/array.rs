@@ -0,0 +1,277 @@
+//! Owned array with tracking of initialized items.
+
+use core::mem::MaybeUninit;
+use core::ops::{Bound, Deref, DerefMut, RangeBounds};
+use crate::slice::Cursor;
+
+pub use crate::slice::Drain;
+
+/// Array of `N` possibly uninitialized items, first `len()` of which are
+/// initialized.
+///
+/// This is basically a vector stored on the stack: it doesn't allocate and
+/// it drops the initialized items when dropped. It's built on top of
+/// `Cursor` over `[MaybeUninit<T>; N]`, but unlike `Cursor`, it allows moving
+/// out arbitrary items.
+pub struct UninitArray<T, const N: usize> {
+    cursor: Cursor<T, [MaybeUninit<T>; N]>,
+}
+
+impl<T, const N: usize> UninitArray<T, N> {
+    /// Creates an empty array.
+    pub fn new() -> Self {
+        UninitArray {
+            cursor: Cursor::new([(); N].map(|_| MaybeUninit::uninit())),
+        }
+    }
+
+    /// Returns the maximum number of items that can be stored in the array.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if no more items can be pushed.
+    pub fn is_full(&self) -> bool {
+        self.cursor.remaining_count() == 0
+    }
+
+    /// Appends an item to the end of the array.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the array is full.
+    pub fn push(&mut self, item: T) -> &mut T {
+        match self.cursor.push(item) {
+            Ok(item) => item,
+            Err(_) => panic!("pushing into full UninitArray"),
+        }
+    }
+
+    /// Attempts to append an item to the end of the array, returning it back
+    /// if the array is full.
+    pub fn try_push(&mut self, item: T) -> Result<&mut T, T> {
+        self.cursor.push(item)
+    }
+
+    /// Removes the last item and returns it.
+    pub fn pop(&mut self) -> Option<T> {
+        self.cursor.pop_owned()
+    }
+
+    /// Inserts an item at given index, shifting all items after it to the
+    /// right.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index > len` or if the array is full.
+    pub fn insert(&mut self, index: usize, item: T) {
+        if index > self.len() {
+            panic!("insertion index (is {}) should be <= len (is {})", index, self.len());
+        }
+        self.push(item);
+        self[index..].rotate_right(1);
+    }
+
+    /// Removes the item at given index, shifting all items after it to the
+    /// left.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        if index >= self.len() {
+            panic!("removal index (is {}) should be < len (is {})", index, self.len());
+        }
+        self[index..].rotate_left(1);
+        self.pop().expect("the array is not empty")
+    }
+
+    /// Removes the item at given index and replaces it with the last item.
+    ///
+    /// This doesn't preserve ordering, but is O(1).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        if index >= self.len() {
+            panic!("swap_remove index (is {}) should be < len (is {})", index, self.len());
+        }
+        let last = self.len() - 1;
+        self.swap(index, last);
+        self.pop().expect("the array is not empty")
+    }
+
+    /// Shortens the array to `len` items, dropping the rest.
+    ///
+    /// Does nothing if `len` is greater than current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            let to_remove = self.len() - len;
+            let removed: *mut [T] = self.cursor.pop_slice(to_remove);
+            unsafe {
+                core::ptr::drop_in_place(removed);
+            }
+        }
+    }
+
+    /// Removes all items.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Removes the items in the range, returning them as an iterator.
+    ///
+    /// The items that were not consumed by the iterator are dropped when the
+    /// iterator is dropped. If the iterator is leaked, they are leaked too.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the range is out of bounds.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).expect("range start overflowed"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).expect("range end overflowed"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        if start > end || end > len {
+            panic!("drain range {}..{} out of bounds of UninitArray of length {}", start, end, len);
+        }
+
+        // Move the drained items to the end, so they can be popped.
+        self[start..].rotate_left(end - start);
+        self.cursor.drain_back(end - start)
+    }
+
+    /// Clones and appends all items of the slice.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there's not enough space for all items.
+    pub fn extend_from_slice(&mut self, slice: &[T]) where T: Clone {
+        if slice.len() > self.cursor.remaining_count() {
+            panic!("not enough space in UninitArray for {} items", slice.len());
+        }
+        self.cursor.push_iter(slice.iter().cloned());
+    }
+
+    /// Converts the array into initialized array if it's full.
+    pub fn into_inner(self) -> Result<[T; N], Self> {
+        self.cursor.try_cast_initialized().map_err(|cursor| UninitArray { cursor })
+    }
+}
+
+impl<T, const N: usize> Default for UninitArray<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Deref for UninitArray<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.cursor.written()
+    }
+}
+
+impl<T, const N: usize> DerefMut for UninitArray<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.cursor.written_mut()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for UninitArray<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for UninitArray<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::cell::Cell;
+    use crate::test_util::DropCounter;
+
+
+    #[test]
+    fn push_pop() {
+        let mut arr = UninitArray::<u8, 3>::new();
+        arr.push(1);
+        arr.push(2);
+        assert_eq!(arr.try_push(3), Ok(&mut 3));
+        assert_eq!(arr.try_push(4), Err(4));
+        assert_eq!(&*arr, &[1, 2, 3]);
+        assert_eq!(arr.pop(), Some(3));
+        assert_eq!(arr.pop(), Some(2));
+        assert_eq!(arr.pop(), Some(1));
+        assert_eq!(arr.pop(), None);
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut arr = UninitArray::<u8, 5>::new();
+        arr.extend_from_slice(&[1, 2, 4]);
+        arr.insert(2, 3);
+        arr.insert(0, 0);
+        assert_eq!(&*arr, &[0, 1, 2, 3, 4]);
+        assert_eq!(arr.remove(1), 1);
+        assert_eq!(arr.swap_remove(0), 0);
+        assert_eq!(&*arr, &[4, 2, 3]);
+        arr.truncate(1);
+        assert_eq!(&*arr, &[4]);
+    }
+
+    #[test]
+    fn drain() {
+        let mut arr = UninitArray::<u8, 5>::new();
+        arr.extend_from_slice(&[0, 1, 2, 3, 4]);
+        assert!(arr.drain(1..3).eq([1, 2].iter().copied()));
+        assert_eq!(&*arr, &[0, 3, 4]);
+        assert!(arr.drain(..).rev().eq([4, 3, 0].iter().copied()));
+        assert!(arr.is_empty());
+    }
+
+    #[test]
+    fn into_inner() {
+        let mut arr = UninitArray::<u8, 2>::new();
+        arr.push(1);
+        let mut arr = arr.into_inner().unwrap_err();
+        arr.push(2);
+        assert_eq!(arr.into_inner().unwrap_or_else(|_| panic!("array not full")), [1, 2]);
+    }
+
+    #[test]
+    fn drops() {
+        let counter = Cell::new(0);
+        let mut arr = UninitArray::<DropCounter, 8>::new();
+        for _ in 0..6 {
+            arr.push(DropCounter(&counter));
+        }
+        drop(arr.pop());
+        assert_eq!(counter.get(), 1);
+        drop(arr.remove(0));
+        assert_eq!(counter.get(), 2);
+        arr.truncate(3);
+        assert_eq!(counter.get(), 3);
+        let mut drain = arr.drain(1..);
+        drop(drain.next());
+        assert_eq!(counter.get(), 4);
+        drop(drain);
+        assert_eq!(counter.get(), 5);
+        drop(arr);
+        assert_eq!(counter.get(), 6);
+    }
+}
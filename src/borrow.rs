@@ -6,6 +6,10 @@ use crate::zeroed::ZeroValid;
 ///
 /// It's analogous to `core::borrow::Borrow`, expect it works with
 /// `MaybeUninit` values.
+///
+/// # Safety
+///
+/// `borrow_uninit` must always return a reference to the same memory location.
 pub unsafe trait BorrowUninit<Borrowed> {
     /// Borrows maybe uninitialized value.
     ///
@@ -26,6 +30,11 @@ pub unsafe trait BorrowUninit<Borrowed> {
 ///
 /// It's analogous to `core::borrow::Borrow`, expect it works with
 /// `MaybeUninit` values.
+///
+/// # Safety
+///
+/// `borrow_out` must always return a reference to the same memory location as
+/// `borrow_uninit` does.
 pub unsafe trait BorrowOut<Borrowed>: BorrowUninit<Borrowed> {
     /// Mutably borrows maybe uninitialized value.
     ///
@@ -118,11 +127,11 @@ unsafe impl<T> BorrowOut<T> for &mut T {
     }
 
     fn default_if_needed(&mut self) -> &mut T where T: Default {
-        *self
+        self
     }
 
     fn zeroed_if_needed(&mut self) -> &mut T where T: ZeroValid {
-        *self
+        self
     }
 }
 
@@ -162,11 +171,11 @@ unsafe impl<T> BorrowOut<T> for core::cell::RefMut<'_, T> {
     }
 
     fn default_if_needed(&mut self) -> &mut T where T: Default {
-        &mut **self
+        self
     }
 
     fn zeroed_if_needed(&mut self) -> &mut T where T: ZeroValid {
-        &mut **self
+        self
     }
 }
 
@@ -188,25 +197,93 @@ unsafe impl<T> BorrowOut<T> for core::cell::RefMut<'_, MaybeUninit<T>> {
     }
 }
 
-/*
-// This would be a great shortcut, but unfortunately T can impl Deref with Target=T
-// Type inequality bounds would help.
-use core::ops::{Deref,DerefMut};
-use crate::deref_markers::SameDataDeref;
+// A blanket impl for `SameDataDeref` types (like the one for `BorrowOutSlice`)
+// would conflict with the impls for `T` because `T` may deref to itself, so
+// the impls for smart pointers are listed explicitly.
+macro_rules! impl_borrow_deref {
+    ($({$($generics:tt)*} $ptr:ty;)*) => {
+        $(
+            unsafe impl<$($generics)*> BorrowUninit<T> for $ptr {
+                fn borrow_uninit(&self) -> &MaybeUninit<T> {
+                    (**self).borrow_uninit()
+                }
+            }
+
+            unsafe impl<$($generics)*> BorrowOut<T> for $ptr {
+                fn borrow_out(&mut self) -> Out<'_, T> {
+                    (**self).borrow_out()
+                }
+
+                fn default_if_needed(&mut self) -> &mut T where T: Default {
+                    (**self).default_if_needed()
+                }
+
+                fn zeroed_if_needed(&mut self) -> &mut T where T: ZeroValid {
+                    (**self).zeroed_if_needed()
+                }
+            }
+        )*
+    }
+}
 
-unsafe impl<T, U> BorrowUninit<U> for T where T: SameDataDeref + Deref + ?Sized,
-                                              T::Target: BorrowUninit<U> {
+impl_borrow_deref! {
+    {T} core::mem::ManuallyDrop<T>;
+    {T} core::mem::ManuallyDrop<MaybeUninit<T>>;
+}
 
-    fn borrow_uninit(&self) -> &MaybeUninit<U> {
-        self.borrow_uninit()
-    }
+#[cfg(feature = "alloc")]
+impl_borrow_deref! {
+    {T} alloc::boxed::Box<T>;
+    {T} alloc::boxed::Box<MaybeUninit<T>>;
+    {T: Ord} alloc::collections::binary_heap::PeekMut<'_, T>;
 }
 
-unsafe impl<T, U> BorrowOut<U> for T where T: SameDataDeref + Deref + DerefMut + ?Sized,
-                                                 T::Target: BorrowOut<U> {
+#[cfg(feature = "std")]
+impl_borrow_deref! {
+    {T} std::sync::MutexGuard<'_, T>;
+    {T} std::sync::MutexGuard<'_, MaybeUninit<T>>;
+    {T} std::sync::RwLockWriteGuard<'_, T>;
+    {T} std::sync::RwLockWriteGuard<'_, MaybeUninit<T>>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fill(mut out: impl BorrowOut<u32>) -> u32 {
+        *out.init(42)
+    }
+
+    #[test]
+    fn manually_drop() {
+        assert_eq!(fill(core::mem::ManuallyDrop::new(0)), 42);
+        assert_eq!(fill(core::mem::ManuallyDrop::new(MaybeUninit::uninit())), 42);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn boxed() {
+        use alloc::boxed::Box;
+
+        assert_eq!(fill(Box::new(0)), 42);
+        let mut boxed = Box::new(MaybeUninit::uninit());
+        assert_eq!(*BorrowOut::<u32>::zeroed_if_needed(&mut boxed), 0);
+        assert_eq!(fill(boxed), 42);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn peek_mut() {
+        let mut heap = alloc::collections::BinaryHeap::from(alloc::vec![1, 5, 3]);
+        fill(heap.peek_mut().expect("heap is not empty"));
+        assert_eq!(heap.into_sorted_vec(), [1, 3, 42]);
+    }
 
-    fn borrow_out(&self) -> Out<'_, U> {
-        self.borrow_out()
+    #[test]
+    #[cfg(feature = "std")]
+    fn mutex_guard() {
+        let mutex = std::sync::Mutex::new(MaybeUninit::uninit());
+        fill(mutex.lock().expect("mutex is not poisoned"));
+        assert_eq!(unsafe { mutex.lock().expect("mutex is not poisoned").assume_init() }, 42);
     }
 }
-*/
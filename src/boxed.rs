@@ -1,33 +1,432 @@
 //! Helpers for using boxes.
 
+use alloc::alloc::Layout;
 use alloc::boxed::Box;
+use core::convert::Infallible;
+use core::fmt;
 use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+use crate::cast::SlicePointerMut;
+use crate::slice::{BorrowOutSlice, OutSlice};
+
+/// Slice types which can be constructed by placement constructors.
+///
+/// This is only implemented for `[T]` and allows the extension traits to
+/// provide constructors for slices.
+///
+/// # Safety
+///
+/// `from_slice_ptr` must return the same pointer with the same length.
+pub unsafe trait Slice {
+    /// Type of the items stored in the slice.
+    type Item;
+
+    /// Converts the pointer to the slice into the pointer to `Self`.
+    fn from_slice_ptr(ptr: *mut [Self::Item]) -> *mut Self;
+}
+
+unsafe impl<T> Slice for [T] {
+    type Item = T;
+
+    fn from_slice_ptr(ptr: *mut [Self::Item]) -> *mut Self {
+        ptr
+    }
+}
+
+/// Array types which can be constructed by placement constructors.
+///
+/// This is only implemented for `[T; N]` and allows the extension traits to
+/// provide constructors for arrays.
+///
+/// # Safety
+///
+/// The type must be an array of `LEN` items of type `Item`.
+pub unsafe trait Array: Sized {
+    /// Type of the items stored in the array.
+    type Item;
+
+    /// The number of items in the array.
+    const LEN: usize;
+}
+
+unsafe impl<T, const N: usize> Array for [T; N] {
+    type Item = T;
+
+    const LEN: usize = N;
+}
+
+/// Error returned when memory allocation fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError {
+    _private: (),
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocError {}
+
+/// Allocates memory for given layout, returning a dangling pointer if the
+/// layout is zero-sized.
+fn try_alloc<T>(layout: Result<Layout, alloc::alloc::LayoutError>) -> Result<*mut T, AllocError> {
+    let layout = layout.map_err(|_| AllocError { _private: () })?;
+    if layout.size() == 0 {
+        return Ok(NonNull::dangling().as_ptr());
+    }
+    let ptr = unsafe { alloc::alloc::alloc(layout) };
+    if ptr.is_null() {
+        Err(AllocError { _private: () })
+    } else {
+        Ok(ptr as *mut T)
+    }
+}
+
+/// Allocates uninitialized slice, returning an error instead of aborting if
+/// the allocation fails.
+pub(crate) fn try_new_uninit_slice<T>(len: usize) -> Result<Box<[MaybeUninit<T>]>, AllocError> {
+    let ptr = try_alloc::<MaybeUninit<T>>(Layout::array::<MaybeUninit<T>>(len))?;
+    unsafe {
+        Ok(Box::from_raw(core::ptr::slice_from_raw_parts_mut(ptr, len)))
+    }
+}
+
+/// Allocates uninitialized slice, aborting like `Box::new` does if the
+/// allocation fails.
+fn new_uninit_slice<T>(len: usize) -> Box<[MaybeUninit<T>]> {
+    let layout = Layout::array::<MaybeUninit<T>>(len).expect("capacity overflow");
+    match try_new_uninit_slice(len) {
+        Ok(boxed) => boxed,
+        Err(_) => alloc::alloc::handle_alloc_error(layout),
+    }
+}
+
+/// Allocates uninitialized value, aborting like `Box::new` does if the
+/// allocation fails.
+///
+/// Unlike `Box::new(MaybeUninit::uninit())`, the value is never on the stack.
+fn new_uninit<T>() -> Box<MaybeUninit<T>> {
+    match Box::<T>::try_new_uninit_out() {
+        Ok(boxed) => boxed,
+        Err(_) => alloc::alloc::handle_alloc_error(Layout::new::<T>()),
+    }
+}
+
+/// Initializes the slice with the closure, checking that it returned the
+/// whole slice.
+///
+/// The closure can only get `&mut [T]` pointing to the same memory by
+/// initializing it, so the check makes this function safe. For zero-sized
+/// `T` every slice has the same dangling address, so the check can't tell
+/// them apart, but zero-sized values need no initialization anyway.
+fn init_slice_with<T, E, F>(slice: &mut [MaybeUninit<T>], f: F) -> Result<(), E> where F: FnOnce(&mut OutSlice<T>) -> Result<&mut [T], E> {
+    let out: &mut OutSlice<T> = slice.into();
+    let ptr = out.as_mut_ptr();
+    let len = out.len();
+    let initialized = f(out)?;
+    assert!(initialized.as_mut_ptr() == ptr && initialized.len() == len, "the closure must return the whole initialized slice");
+    Ok(())
+}
+
+/// Initializes the array with values returned from the closure called with
+/// the index of each item.
+///
+/// The already written items are dropped in case of error.
+fn init_array_with<A: Array, E, F>(array: &mut MaybeUninit<A>, f: F) -> Result<(), E> where F: FnMut(usize) -> Result<A::Item, E> {
+    let slice = unsafe {
+        core::slice::from_raw_parts_mut(array.as_mut_ptr() as *mut MaybeUninit<A::Item>, A::LEN)
+    };
+    slice.try_init_with(f).map(drop)
+}
+
+/// Generates placement constructors for shared pointers (`Rc` and `Arc`).
+///
+/// The value is initialized in a `Box` and then moved into the shared
+/// pointer, since allocating `Rc` and `Arc` uninitialized (`new_uninit`)
+/// requires a newer compiler. The move is a copy between two heap
+/// allocations, so the value is still never on the stack.
+macro_rules! shared_ext {
+    ($ptr:ident, $ext:ident) => {
+        #[doc = concat!("Extension trait allowing placement new operation for `", stringify!($ptr), "`.")]
+        ///
+        /// See `BoxExt` for documentation of the methods.
+        pub trait $ext: Sized {
+            /// Type stored in the pointer.
+            type Stored: ?Sized;
+
+            /// Creates the pointer with the closure running after the memory
+            /// is allocated, so that the return value can be written into it
+            /// diretly.
+            fn new_with<F: FnOnce() -> Self::Stored>(f: F) -> Self where Self::Stored: Sized;
+
+            /// Creates the pointer with the fallible closure running after the
+            /// memory is allocated.
+            fn try_new_with<E, F: FnOnce() -> Result<Self::Stored, E>>(f: F) -> Result<Self, E> where Self::Stored: Sized;
+
+            /// Creates the pointer to `[T]` of given length initialized by the
+            /// closure.
+            ///
+            /// # Panics
+            ///
+            /// This function panics if the closure returns a different slice.
+            fn new_with_len<Item, F>(len: usize, f: F) -> Self where Self::Stored: $crate::boxed::Slice<Item=Item>, F: FnOnce(&mut $crate::slice::OutSlice<Item>) -> &mut [Item];
+
+            /// Creates the pointer to `[T]` of given length initialized by the
+            /// fallible closure.
+            ///
+            /// # Panics
+            ///
+            /// This function panics if the closure returns a different slice.
+            fn try_new_with_len<Item, E, F>(len: usize, f: F) -> Result<Self, E> where Self::Stored: $crate::boxed::Slice<Item=Item>, F: FnOnce(&mut $crate::slice::OutSlice<Item>) -> Result<&mut [Item], E>;
+
+            /// Creates the pointer to `[T; N]` with items returned from the
+            /// closure called with the index of each item.
+            ///
+            /// The already created items are dropped if the closure panics.
+            fn new_from_fn<Item, F: FnMut(usize) -> Item>(f: F) -> Self where Self::Stored: $crate::boxed::Array<Item=Item>;
+
+            /// Creates the pointer to `[T; N]` with items returned from the
+            /// fallible closure called with the index of each item.
+            ///
+            /// The already created items are dropped in case of error or
+            /// panic.
+            fn try_new_from_fn<Item, E, F: FnMut(usize) -> Result<Item, E>>(f: F) -> Result<Self, E> where Self::Stored: $crate::boxed::Array<Item=Item>;
+        }
+
+        impl<T: ?Sized> $ext for $ptr<T> {
+            type Stored = T;
+
+            fn new_with<F: FnOnce() -> T>(f: F) -> Self where T: Sized {
+                $ptr::from(<alloc::boxed::Box<T> as $crate::boxed::BoxExt>::new_with(f))
+            }
+
+            fn try_new_with<E, F: FnOnce() -> Result<T, E>>(f: F) -> Result<Self, E> where T: Sized {
+                <alloc::boxed::Box<T> as $crate::boxed::BoxExt>::try_new_with(f).map($ptr::from)
+            }
+
+            fn new_with_len<Item, F>(len: usize, f: F) -> Self where T: $crate::boxed::Slice<Item=Item>, F: FnOnce(&mut $crate::slice::OutSlice<Item>) -> &mut [Item] {
+                $ptr::from(<alloc::boxed::Box<T> as $crate::boxed::BoxExt>::new_with_len(len, f))
+            }
+
+            fn try_new_with_len<Item, E, F>(len: usize, f: F) -> Result<Self, E> where T: $crate::boxed::Slice<Item=Item>, F: FnOnce(&mut $crate::slice::OutSlice<Item>) -> Result<&mut [Item], E> {
+                <alloc::boxed::Box<T> as $crate::boxed::BoxExt>::try_new_with_len(len, f).map($ptr::from)
+            }
+
+            fn new_from_fn<Item, F: FnMut(usize) -> Item>(f: F) -> Self where T: $crate::boxed::Array<Item=Item> {
+                $ptr::from(<alloc::boxed::Box<T> as $crate::boxed::BoxExt>::new_from_fn(f))
+            }
+
+            fn try_new_from_fn<Item, E, F: FnMut(usize) -> Result<Item, E>>(f: F) -> Result<Self, E> where T: $crate::boxed::Array<Item=Item> {
+                <alloc::boxed::Box<T> as $crate::boxed::BoxExt>::try_new_from_fn(f).map($ptr::from)
+            }
+        }
+    }
+}
+
+pub(crate) use shared_ext;
 
 /// Extension trait allowing placement new operation.
-pub trait BoxExt {
+///
+/// The `try_` prefix of the constructors refers to the fallible closure. Just
+/// like `Box::new`, they abort if the allocation fails. Use
+/// `try_new_uninit_out` or `try_new_uninit_slice_out` to handle allocation
+/// failures.
+pub trait BoxExt: Sized {
     /// Type stored in the box.
     type Stored: ?Sized;
 
     /// Creates the `Box<Stored>` with the closure running after the box is
     /// allocated, so that the return value can be written into box diretly.
     fn new_with<F: FnOnce() -> Self::Stored>(f: F) -> Self where Self::Stored: Sized;
+
+    /// Creates the `Box<Stored>` with the fallible closure running after the
+    /// box is allocated.
+    fn try_new_with<E, F: FnOnce() -> Result<Self::Stored, E>>(f: F) -> Result<Self, E> where Self::Stored: Sized;
+
+    /// Creates the `Box<[T]>` of given length initialized by the closure.
+    ///
+    /// The closure must return the whole initialized slice, which can be
+    /// obtained by calling one of the initialization methods of `OutSlice`.
+    /// If the closure panics, the items it already wrote are leaked, since it
+    /// can't be known which ones they are. Use `new_from_fn` for arrays to
+    /// have them dropped.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the closure returns a different slice.
+    fn new_with_len<Item, F>(len: usize, f: F) -> Self where Self::Stored: Slice<Item=Item>, F: FnOnce(&mut OutSlice<Item>) -> &mut [Item];
+
+    /// Creates the `Box<[T]>` of given length initialized by the fallible
+    /// closure.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the closure returns a different slice.
+    fn try_new_with_len<Item, E, F>(len: usize, f: F) -> Result<Self, E> where Self::Stored: Slice<Item=Item>, F: FnOnce(&mut OutSlice<Item>) -> Result<&mut [Item], E>;
+
+    /// Creates the `Box<[T; N]>` with items returned from the closure called
+    /// with the index of each item.
+    ///
+    /// The array is never stored on the stack. The already created items are
+    /// dropped if the closure panics.
+    fn new_from_fn<Item, F: FnMut(usize) -> Item>(f: F) -> Self where Self::Stored: Array<Item=Item>;
+
+    /// Creates the `Box<[T; N]>` with items returned from the fallible
+    /// closure called with the index of each item.
+    ///
+    /// The already created items are dropped in case of error or panic.
+    fn try_new_from_fn<Item, E, F: FnMut(usize) -> Result<Item, E>>(f: F) -> Result<Self, E> where Self::Stored: Array<Item=Item>;
+
+    /// Allocates uninitialized `Box<MaybeUninit<Stored>>`.
+    ///
+    /// Unlike `Box::new`, this returns an error instead of aborting if the
+    /// allocation fails. The value can be initialized using `Out`.
+    fn try_new_uninit_out() -> Result<Box<MaybeUninit<Self::Stored>>, AllocError> where Self::Stored: Sized;
+
+    /// Allocates uninitialized slice of given length.
+    ///
+    /// Unlike `Box::new_uninit_slice`, this returns an error instead of
+    /// aborting if the allocation fails. The slice can be initialized using
+    /// `OutSlice` or `Cursor`.
+    fn try_new_uninit_slice_out<Item>(len: usize) -> Result<Box<[MaybeUninit<Item>]>, AllocError> where Self::Stored: Slice<Item=Item>;
 }
 
 impl<T: ?Sized> BoxExt for Box<T> {
     type Stored = T;
 
-    fn new_with<F: FnOnce() -> Self::Stored>(f: F) -> Self where Self::Stored: Sized {
+    fn new_with<F: FnOnce() -> T>(f: F) -> Self where T: Sized {
         let mut boxed: Box<MaybeUninit<T>> = Box::new(MaybeUninit::uninit());
         unsafe {
             *boxed = MaybeUninit::new(f());
             Box::from_raw(Box::into_raw(boxed) as *mut T)
         }
     }
+
+    fn try_new_with<E, F: FnOnce() -> Result<T, E>>(f: F) -> Result<Self, E> where T: Sized {
+        let mut boxed: Box<MaybeUninit<T>> = Box::new(MaybeUninit::uninit());
+        unsafe {
+            *boxed = MaybeUninit::new(f()?);
+            Ok(Box::from_raw(Box::into_raw(boxed) as *mut T))
+        }
+    }
+
+    fn new_with_len<Item, F>(len: usize, f: F) -> Self where T: Slice<Item=Item>, F: FnOnce(&mut OutSlice<Item>) -> &mut [Item] {
+        match Self::try_new_with_len(len, |out| Ok::<_, Infallible>(f(out))) {
+            Ok(boxed) => boxed,
+            Err(never) => match never {},
+        }
+    }
+
+    fn try_new_with_len<Item, E, F>(len: usize, f: F) -> Result<Self, E> where T: Slice<Item=Item>, F: FnOnce(&mut OutSlice<Item>) -> Result<&mut [Item], E> {
+        let mut boxed = new_uninit_slice::<Item>(len);
+        init_slice_with(&mut boxed, f)?;
+        let (ptr, len) = boxed.into_raw_parts_mut();
+        unsafe {
+            let ptr = core::ptr::slice_from_raw_parts_mut(ptr as *mut Item, len);
+            Ok(Box::from_raw(T::from_slice_ptr(ptr)))
+        }
+    }
+
+    fn new_from_fn<Item, F: FnMut(usize) -> Item>(mut f: F) -> Self where T: Array<Item=Item> {
+        match Self::try_new_from_fn(|i| Ok::<_, Infallible>(f(i))) {
+            Ok(boxed) => boxed,
+            Err(never) => match never {},
+        }
+    }
+
+    fn try_new_from_fn<Item, E, F: FnMut(usize) -> Result<Item, E>>(f: F) -> Result<Self, E> where T: Array<Item=Item> {
+        let mut boxed = new_uninit::<T>();
+        init_array_with(&mut boxed, f)?;
+        unsafe {
+            Ok(Box::from_raw(Box::into_raw(boxed) as *mut T))
+        }
+    }
+
+    fn try_new_uninit_out() -> Result<Box<MaybeUninit<T>>, AllocError> where T: Sized {
+        let ptr = try_alloc::<MaybeUninit<T>>(Ok(Layout::new::<MaybeUninit<T>>()))?;
+        unsafe {
+            Ok(Box::from_raw(ptr))
+        }
+    }
+
+    fn try_new_uninit_slice_out<Item>(len: usize) -> Result<Box<[MaybeUninit<Item>]>, AllocError> where T: Slice<Item=Item> {
+        try_new_uninit_slice(len)
+    }
 }
 
 #[cfg(test)]
-#[test]
-fn test_box_new_with() {
-    let b = Box::new_with(|| 42);
-    assert_eq!(*b, 42);
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_box_new_with() {
+        let b = Box::new_with(|| 42);
+        assert_eq!(*b, 42);
+        let b = Box::try_new_with(|| Err::<u8, _>(42));
+        assert_eq!(b, Err(42));
+    }
+
+    #[test]
+    fn new_with_len() {
+        let b = Box::<[u8]>::new_with_len(4, |out| out.init_with(|i| i as u8));
+        assert_eq!(*b, [0, 1, 2, 3]);
+        let b = Box::<[u8]>::try_new_with_len(4, |out| out.try_init_with(|i| if i < 2 { Ok(0) } else { Err(i) }));
+        assert_eq!(b, Err(2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_with_len_partial() {
+        Box::<[u8]>::new_with_len(4, |out| out[..2].fill(0));
+    }
+
+    #[test]
+    fn new_with_len_zst() {
+        let b = Box::<[()]>::new_with_len(3, |out| out.fill(()));
+        assert_eq!(b.len(), 3);
+        let other = Box::leak(alloc::vec![(); 3].into_boxed_slice());
+        let b = Box::<[()]>::new_with_len(3, move |_| other);
+        assert_eq!(b.len(), 3);
+    }
+
+    #[test]
+    fn new_from_fn() {
+        let b = Box::<[usize; 1 << 16]>::new_from_fn(|i| i);
+        assert_eq!(b[42], 42);
+        assert_eq!(b[(1 << 16) - 1], (1 << 16) - 1);
+        let b = Box::<[usize; 4]>::try_new_from_fn(|i| if i < 2 { Ok(0) } else { Err(i) });
+        assert_eq!(b, Err(2));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn new_from_fn_panic_drops() {
+        use core::cell::Cell;
+        use crate::test_util::DropCounter;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let counter = Cell::new(0);
+        let create = |i| if i < 2 { DropCounter(&counter) } else { panic!("creating item failed") };
+        assert!(catch_unwind(AssertUnwindSafe(|| Box::<[DropCounter; 4]>::new_from_fn(create))).is_err());
+        assert_eq!(counter.get(), 2);
+        assert!(catch_unwind(AssertUnwindSafe(|| <alloc::rc::Rc<[DropCounter; 4]> as crate::rc::RcExt>::new_from_fn(create))).is_err());
+        assert_eq!(counter.get(), 4);
+        assert!(catch_unwind(AssertUnwindSafe(|| <alloc::sync::Arc<[DropCounter; 4]> as crate::sync::ArcExt>::try_new_from_fn(|i| Ok::<_, ()>(create(i))))).is_err());
+        assert_eq!(counter.get(), 6);
+    }
+
+    #[test]
+    fn try_new_uninit_out() {
+        let mut b = Box::<u64>::try_new_uninit_out().expect("allocation failed");
+        assert_eq!(*crate::Out::from(&mut *b).write(42), 42);
+        let b = Box::<[u8]>::try_new_uninit_slice_out(16).expect("allocation failed");
+        assert_eq!(b.len(), 16);
+        let b = Box::<[()]>::try_new_uninit_slice_out(16).expect("allocation failed");
+        assert_eq!(b.len(), 16);
+        assert!(Box::<[u8]>::try_new_uninit_slice_out(isize::MAX as usize + 1).is_err());
+        assert!(Box::<[u16]>::try_new_uninit_slice_out(usize::MAX).is_err());
+    }
 }
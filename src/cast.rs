@@ -4,1167 +4,324 @@
 //! pointer types as well as non-pointer types. While it succeeds at its goal
 //! in some ways, it fails in other ways.
 //!
-//! Unfortunately it's a bunch of `unsafe` code and it reached the point at
-//! which I'm not as confident in its soundness as I would like to be.
+//! It's a bunch of `unsafe` code, so each `CastArrHelper` impl is covered by
+//! tests which are supposed to be run under Miri too (`cargo +nightly miri
+//! test --all-features`).
 //!
 //! Currently it mainly attempts to enable `Cursor::try_cast_initialized` to
 //! work with all kinds of sensible arrays: `[T; N]`, `UniqueRefType[T; N]`,
-//! `UniqueRefType<[T]>` for `T` and `MaybeUninit<T>`. It does work for
-//! `MaybeUninit<T>` arrays but not for `T` arrays. Which isn't too important
-//! right now, but it'd be nice to resolve it.
-
-use core::mem::MaybeUninit;
-use core::ops::{Deref, DerefMut};
-use crate::deref_markers::StableDeref;
-
-// See rust-lang/rust/issues/61956
-// Thanks to HadrienG2 for the workaround!
-unsafe fn transmute_workaround_size_bug_super_dangerous_ignores_size<T, U>(mut x: T) -> U {
-    assert_eq!(core::mem::size_of::<T>(), core::mem::size_of::<U>());
-
-    let ptr = &mut x as *mut _ as *mut U;
-    let res = ptr.read();
-    core::mem::forget(x);
-    res
-}
-
-/// Array type constructor
-///
-/// Constructs an array with same (possibly dynamic) size and different item.
-pub trait ArrTc<Item> {
-    /// Constructed array.
-    type Arr: ?Sized;
-    /// Dereferenced version of array.
-    type Kind: ?Sized;
-}
-
-impl<'a, U: 'a, A: ArrTc<U> + ?Sized> ArrTc<U> for &'a mut A {
-    type Arr = &'a mut <A as ArrTc<U>>::Arr;
-    type Kind = <A as ArrTc<U>>::Kind;
-}
-
-#[cfg(feature = "alloc")]
-impl<U, A: ArrTc<U> + ?Sized> ArrTc<U> for alloc::boxed::Box<A> {
-    type Arr = alloc::boxed::Box<<A as ArrTc<U>>::Arr>;
-    type Kind = <A as ArrTc<U>>::Kind;
-}
-
-impl<T, U> ArrTc<U> for [T] {
-    type Arr = [U];
-    type Kind = [U];
-}
-
-impl<T, U> ArrTc<U> for [T; 0] {
-    type Arr = [U; 0];
-    type Kind = [U; 0];
-}
-
-impl<T, U> ArrTc<U> for [T; 1] {
-    type Arr = [U; 1];
-    type Kind = [U; 1];
-}
-
-impl<T, U> ArrTc<U> for [T; 2] {
-    type Arr = [U; 2];
-    type Kind = [U; 2];
-}
-
-impl<T, U> ArrTc<U> for [T; 3] {
-    type Arr = [U; 3];
-    type Kind = [U; 3];
-}
-
-impl<T, U> ArrTc<U> for [T; 4] {
-    type Arr = [U; 4];
-    type Kind = [U; 4];
-}
-
-impl<T, U> ArrTc<U> for [T; 5] {
-    type Arr = [U; 5];
-    type Kind = [U; 5];
-}
-
-impl<T, U> ArrTc<U> for [T; 6] {
-    type Arr = [U; 6];
-    type Kind = [U; 6];
-}
-
-impl<T, U> ArrTc<U> for [T; 7] {
-    type Arr = [U; 7];
-    type Kind = [U; 7];
-}
-
-impl<T, U> ArrTc<U> for [T; 8] {
-    type Arr = [U; 8];
-    type Kind = [U; 8];
-}
-
-impl<T, U> ArrTc<U> for [T; 9] {
-    type Arr = [U; 9];
-    type Kind = [U; 9];
-}
-
-impl<T, U> ArrTc<U> for [T; 10] {
-    type Arr = [U; 10];
-    type Kind = [U; 10];
-}
-
-impl<T, U> ArrTc<U> for [T; 11] {
-    type Arr = [U; 11];
-    type Kind = [U; 11];
-}
-
-impl<T, U> ArrTc<U> for [T; 12] {
-    type Arr = [U; 12];
-    type Kind = [U; 12];
-}
-
-impl<T, U> ArrTc<U> for [T; 13] {
-    type Arr = [U; 13];
-    type Kind = [U; 13];
-}
-
-impl<T, U> ArrTc<U> for [T; 14] {
-    type Arr = [U; 14];
-    type Kind = [U; 14];
-}
-
-impl<T, U> ArrTc<U> for [T; 15] {
-    type Arr = [U; 15];
-    type Kind = [U; 15];
-}
-
-impl<T, U> ArrTc<U> for [T; 16] {
-    type Arr = [U; 16];
-    type Kind = [U; 16];
-}
-
-impl<T, U> ArrTc<U> for [T; 17] {
-    type Arr = [U; 17];
-    type Kind = [U; 17];
-}
-
-impl<T, U> ArrTc<U> for [T; 18] {
-    type Arr = [U; 18];
-    type Kind = [U; 18];
-}
-
-impl<T, U> ArrTc<U> for [T; 19] {
-    type Arr = [U; 19];
-    type Kind = [U; 19];
-}
-
-impl<T, U> ArrTc<U> for [T; 20] {
-    type Arr = [U; 20];
-    type Kind = [U; 20];
-}
-
-impl<T, U> ArrTc<U> for [T; 21] {
-    type Arr = [U; 21];
-    type Kind = [U; 21];
-}
-
-impl<T, U> ArrTc<U> for [T; 22] {
-    type Arr = [U; 22];
-    type Kind = [U; 22];
-}
-
-impl<T, U> ArrTc<U> for [T; 23] {
-    type Arr = [U; 23];
-    type Kind = [U; 23];
-}
-
-impl<T, U> ArrTc<U> for [T; 24] {
-    type Arr = [U; 24];
-    type Kind = [U; 24];
-}
-
-impl<T, U> ArrTc<U> for [T; 25] {
-    type Arr = [U; 25];
-    type Kind = [U; 25];
-}
-
-impl<T, U> ArrTc<U> for [T; 26] {
-    type Arr = [U; 26];
-    type Kind = [U; 26];
-}
-
-impl<T, U> ArrTc<U> for [T; 27] {
-    type Arr = [U; 27];
-    type Kind = [U; 27];
-}
-
-impl<T, U> ArrTc<U> for [T; 28] {
-    type Arr = [U; 28];
-    type Kind = [U; 28];
-}
-
-impl<T, U> ArrTc<U> for [T; 29] {
-    type Arr = [U; 29];
-    type Kind = [U; 29];
-}
-
-impl<T, U> ArrTc<U> for [T; 30] {
-    type Arr = [U; 30];
-    type Kind = [U; 30];
-}
-
-impl<T, U> ArrTc<U> for [T; 31] {
-    type Arr = [U; 31];
-    type Kind = [U; 31];
-}
-
-impl<T, U> ArrTc<U> for [T; 32] {
-    type Arr = [U; 32];
-    type Kind = [U; 32];
-}
-
-/// Initialization type constructor
-///
-/// For a possibly uninitialized type it constructs an initialied version of the type.
-pub trait InitTc<Item>: ArrTc<Item> {
-    /// Resulting type
-    type Output: Sized;
-
-    /// Casts the types.
-    unsafe fn cast(self) -> Self::Output;
-}
-
-impl<Item, T> InitTc<Item> for T where T: ArrTc<Item>, (Self, <Self as ArrTc<Item>>::Kind): CastArrHelper<From=Self> {
-    type Output = <(Self, <Self as ArrTc<Item>>::Kind) as CastArrHelper>::To;
-
-    unsafe fn cast(self) -> Self::Output {
-        <(Self, <Self as ArrTc<Item>>::Kind)>::cast(self)
-    }
-}
-
-/// A trick to work arround the limitation of Rust where the compiler can't understand that two
-/// impls with different associated types aren't conflicting.
-pub unsafe trait CastArrHelper {
-    /// Type from which we are casting
-    type From: Sized;
-
-    /// Type into which we are casting
-    type To: Sized;
-
-    /// Cast those two things
-    unsafe fn cast(from: Self::From) -> Self::To;
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T]) where P: SlicePointerMut<Item=MaybeUninit<T>> + Deref<Target=[MaybeUninit<T>]> + PtrTc<[T]>, <P as PtrTc<[T]>>::Pointer: SlicePointerMut<Item=T> + Deref<Target=[T]> {
-    type From = P;
-    type To = <P as PtrTc<[T]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        let (ptr, len) = from.into_raw_parts_mut();
-        Self::To::from_raw_parts_mut(ptr as *mut <Self::To as SlicePointerMut>::Item, len)
-    }
-}
-
-// Dear reader, I really, really recommend learning Vim and its record tool (`q` key).
-// It's extremely helpful at doing stuff like this.
-unsafe impl<T> CastArrHelper for ([T; 0], [T; 0]) {
-    type From = [T; 0];
-    type To = [T; 0];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 0], [T; 0]) {
-    type From = [MaybeUninit<T>; 0];
-    type To = [T; 0];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 0]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 0]> + PtrTc<[T; 0]>, <P as PtrTc<[T; 0]>>::Pointer: PointerMut + Deref<Target=[T; 0]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 0]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 1], [T; 1]) {
-    type From = [T; 1];
-    type To = [T; 1];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 1], [T; 1]) {
-    type From = [MaybeUninit<T>; 1];
-    type To = [T; 1];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 1]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 1]> + PtrTc<[T; 1]>, <P as PtrTc<[T; 1]>>::Pointer: PointerMut + Deref<Target=[T; 1]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 1]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 2], [T; 2]) {
-    type From = [T; 2];
-    type To = [T; 2];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 2], [T; 2]) {
-    type From = [MaybeUninit<T>; 2];
-    type To = [T; 2];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 2]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 2]> + PtrTc<[T; 2]>, <P as PtrTc<[T; 2]>>::Pointer: PointerMut + Deref<Target=[T; 2]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 2]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 3], [T; 3]) {
-    type From = [T; 3];
-    type To = [T; 3];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 3], [T; 3]) {
-    type From = [MaybeUninit<T>; 3];
-    type To = [T; 3];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 3]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 3]> + PtrTc<[T; 3]>, <P as PtrTc<[T; 3]>>::Pointer: PointerMut + Deref<Target=[T; 3]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 3]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 4], [T; 4]) {
-    type From = [T; 4];
-    type To = [T; 4];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 4], [T; 4]) {
-    type From = [MaybeUninit<T>; 4];
-    type To = [T; 4];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 4]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 4]> + PtrTc<[T; 4]>, <P as PtrTc<[T; 4]>>::Pointer: PointerMut + Deref<Target=[T; 4]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 4]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 5], [T; 5]) {
-    type From = [T; 5];
-    type To = [T; 5];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 5], [T; 5]) {
-    type From = [MaybeUninit<T>; 5];
-    type To = [T; 5];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 5]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 5]> + PtrTc<[T; 5]>, <P as PtrTc<[T; 5]>>::Pointer: PointerMut + Deref<Target=[T; 5]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 5]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 6], [T; 6]) {
-    type From = [T; 6];
-    type To = [T; 6];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 6], [T; 6]) {
-    type From = [MaybeUninit<T>; 6];
-    type To = [T; 6];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 6]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 6]> + PtrTc<[T; 6]>, <P as PtrTc<[T; 6]>>::Pointer: PointerMut + Deref<Target=[T; 6]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 6]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 7], [T; 7]) {
-    type From = [T; 7];
-    type To = [T; 7];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 7], [T; 7]) {
-    type From = [MaybeUninit<T>; 7];
-    type To = [T; 7];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 7]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 7]> + PtrTc<[T; 7]>, <P as PtrTc<[T; 7]>>::Pointer: PointerMut + Deref<Target=[T; 7]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 7]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 8], [T; 8]) {
-    type From = [T; 8];
-    type To = [T; 8];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 8], [T; 8]) {
-    type From = [MaybeUninit<T>; 8];
-    type To = [T; 8];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 8]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 8]> + PtrTc<[T; 8]>, <P as PtrTc<[T; 8]>>::Pointer: PointerMut + Deref<Target=[T; 8]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 8]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 9], [T; 9]) {
-    type From = [T; 9];
-    type To = [T; 9];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 9], [T; 9]) {
-    type From = [MaybeUninit<T>; 9];
-    type To = [T; 9];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 9]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 9]> + PtrTc<[T; 9]>, <P as PtrTc<[T; 9]>>::Pointer: PointerMut + Deref<Target=[T; 9]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 9]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 10], [T; 10]) {
-    type From = [T; 10];
-    type To = [T; 10];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 10], [T; 10]) {
-    type From = [MaybeUninit<T>; 10];
-    type To = [T; 10];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 10]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 10]> + PtrTc<[T; 10]>, <P as PtrTc<[T; 10]>>::Pointer: PointerMut + Deref<Target=[T; 10]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 10]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 11], [T; 11]) {
-    type From = [T; 11];
-    type To = [T; 11];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 11], [T; 11]) {
-    type From = [MaybeUninit<T>; 11];
-    type To = [T; 11];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 11]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 11]> + PtrTc<[T; 11]>, <P as PtrTc<[T; 11]>>::Pointer: PointerMut + Deref<Target=[T; 11]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 11]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 12], [T; 12]) {
-    type From = [T; 12];
-    type To = [T; 12];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 12], [T; 12]) {
-    type From = [MaybeUninit<T>; 12];
-    type To = [T; 12];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 12]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 12]> + PtrTc<[T; 12]>, <P as PtrTc<[T; 12]>>::Pointer: PointerMut + Deref<Target=[T; 12]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 12]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 13], [T; 13]) {
-    type From = [T; 13];
-    type To = [T; 13];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 13], [T; 13]) {
-    type From = [MaybeUninit<T>; 13];
-    type To = [T; 13];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 13]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 13]> + PtrTc<[T; 13]>, <P as PtrTc<[T; 13]>>::Pointer: PointerMut + Deref<Target=[T; 13]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 13]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 14], [T; 14]) {
-    type From = [T; 14];
-    type To = [T; 14];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 14], [T; 14]) {
-    type From = [MaybeUninit<T>; 14];
-    type To = [T; 14];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 14]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 14]> + PtrTc<[T; 14]>, <P as PtrTc<[T; 14]>>::Pointer: PointerMut + Deref<Target=[T; 14]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 14]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 15], [T; 15]) {
-    type From = [T; 15];
-    type To = [T; 15];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 15], [T; 15]) {
-    type From = [MaybeUninit<T>; 15];
-    type To = [T; 15];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 15]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 15]> + PtrTc<[T; 15]>, <P as PtrTc<[T; 15]>>::Pointer: PointerMut + Deref<Target=[T; 15]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 15]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 16], [T; 16]) {
-    type From = [T; 16];
-    type To = [T; 16];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 16], [T; 16]) {
-    type From = [MaybeUninit<T>; 16];
-    type To = [T; 16];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 16]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 16]> + PtrTc<[T; 16]>, <P as PtrTc<[T; 16]>>::Pointer: PointerMut + Deref<Target=[T; 16]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 16]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 17], [T; 17]) {
-    type From = [T; 17];
-    type To = [T; 17];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 17], [T; 17]) {
-    type From = [MaybeUninit<T>; 17];
-    type To = [T; 17];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 17]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 17]> + PtrTc<[T; 17]>, <P as PtrTc<[T; 17]>>::Pointer: PointerMut + Deref<Target=[T; 17]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 17]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 18], [T; 18]) {
-    type From = [T; 18];
-    type To = [T; 18];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 18], [T; 18]) {
-    type From = [MaybeUninit<T>; 18];
-    type To = [T; 18];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 18]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 18]> + PtrTc<[T; 18]>, <P as PtrTc<[T; 18]>>::Pointer: PointerMut + Deref<Target=[T; 18]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 18]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 19], [T; 19]) {
-    type From = [T; 19];
-    type To = [T; 19];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 19], [T; 19]) {
-    type From = [MaybeUninit<T>; 19];
-    type To = [T; 19];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 19]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 19]> + PtrTc<[T; 19]>, <P as PtrTc<[T; 19]>>::Pointer: PointerMut + Deref<Target=[T; 19]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 19]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 20], [T; 20]) {
-    type From = [T; 20];
-    type To = [T; 20];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 20], [T; 20]) {
-    type From = [MaybeUninit<T>; 20];
-    type To = [T; 20];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 20]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 20]> + PtrTc<[T; 20]>, <P as PtrTc<[T; 20]>>::Pointer: PointerMut + Deref<Target=[T; 20]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 20]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 21], [T; 21]) {
-    type From = [T; 21];
-    type To = [T; 21];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 21], [T; 21]) {
-    type From = [MaybeUninit<T>; 21];
-    type To = [T; 21];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
+//! `UniqueRefType<[T]>` for `T` and `MaybeUninit<T>`. Casting `T` arrays is
+//! a no-op.
+//!
+//! The casts are also available for `UniqueRefType<MaybeUninit<T>>`, where
+//! `UniqueRefType` may be `&mut`, `Box`, `Pin<Box>`, `Rc`, `Arc` or `Vec`
+//! (slices only). `Rc` and `Arc` must not be shared at the time of the cast,
+//! otherwise the cast panics.
+//!
+//! The `AssumeInit` and `Uninit` traits are the convenient interface to the
+//! casts.
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 21]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 21]> + PtrTc<[T; 21]>, <P as PtrTc<[T; 21]>>::Pointer: PointerMut + Deref<Target=[T; 21]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 21]>>::Pointer;
+use core::marker::PhantomData;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::Deref;
+use core::pin::Pin;
+use crate::deref_markers::StableDeref;
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
+/// Array type constructor
+///
+/// Constructs an array with same (possibly dynamic) size and different item.
+pub trait ArrTc<Item> {
+    /// Constructed array.
+    type Arr: ?Sized;
+    /// Dereferenced version of array.
+    type Kind: ?Sized;
+    /// Dereferenced version of the original array.
+    type Source: ?Sized;
 }
 
-unsafe impl<T> CastArrHelper for ([T; 22], [T; 22]) {
-    type From = [T; 22];
-    type To = [T; 22];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+impl<'a, U: 'a, A: ArrTc<U> + ?Sized> ArrTc<U> for &'a mut A {
+    type Arr = &'a mut <A as ArrTc<U>>::Arr;
+    type Kind = <A as ArrTc<U>>::Kind;
+    type Source = <A as ArrTc<U>>::Source;
 }
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 22], [T; 22]) {
-    type From = [MaybeUninit<T>; 22];
-    type To = [T; 22];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+#[cfg(feature = "alloc")]
+impl<U, A: ArrTc<U> + ?Sized> ArrTc<U> for alloc::boxed::Box<A> {
+    type Arr = alloc::boxed::Box<<A as ArrTc<U>>::Arr>;
+    type Kind = <A as ArrTc<U>>::Kind;
+    type Source = <A as ArrTc<U>>::Source;
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 22]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 22]> + PtrTc<[T; 22]>, <P as PtrTc<[T; 22]>>::Pointer: PointerMut + Deref<Target=[T; 22]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 22]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
+#[cfg(feature = "alloc")]
+impl<U, A: ArrTc<U> + ?Sized> ArrTc<U> for alloc::rc::Rc<A> {
+    type Arr = alloc::rc::Rc<<A as ArrTc<U>>::Arr>;
+    type Kind = <A as ArrTc<U>>::Kind;
+    type Source = <A as ArrTc<U>>::Source;
 }
 
-unsafe impl<T> CastArrHelper for ([T; 23], [T; 23]) {
-    type From = [T; 23];
-    type To = [T; 23];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+#[cfg(feature = "alloc")]
+impl<U, A: ArrTc<U> + ?Sized> ArrTc<U> for alloc::sync::Arc<A> {
+    type Arr = alloc::sync::Arc<<A as ArrTc<U>>::Arr>;
+    type Kind = <A as ArrTc<U>>::Kind;
+    type Source = <A as ArrTc<U>>::Source;
 }
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 23], [T; 23]) {
-    type From = [MaybeUninit<T>; 23];
-    type To = [T; 23];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+#[cfg(feature = "alloc")]
+impl<T, U> ArrTc<U> for alloc::vec::Vec<T> {
+    type Arr = alloc::vec::Vec<U>;
+    type Kind = [U];
+    type Source = [T];
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 23]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 23]> + PtrTc<[T; 23]>, <P as PtrTc<[T; 23]>>::Pointer: PointerMut + Deref<Target=[T; 23]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 23]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
+impl<U, P: ArrTc<U>> ArrTc<U> for Pin<P> where <P as ArrTc<U>>::Arr: Sized {
+    type Arr = Pin<<P as ArrTc<U>>::Arr>;
+    type Kind = <P as ArrTc<U>>::Kind;
+    type Source = <P as ArrTc<U>>::Source;
 }
 
-unsafe impl<T> CastArrHelper for ([T; 24], [T; 24]) {
-    type From = [T; 24];
-    type To = [T; 24];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+/// `MaybeUninit<T>` is treated as an array of single item.
+impl<T> ArrTc<T> for MaybeUninit<T> {
+    type Arr = T;
+    type Kind = T;
+    type Source = MaybeUninit<T>;
 }
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 24], [T; 24]) {
-    type From = [MaybeUninit<T>; 24];
-    type To = [T; 24];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+impl<T, U> ArrTc<U> for [T] {
+    type Arr = [U];
+    type Kind = [U];
+    type Source = [T];
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 24]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 24]> + PtrTc<[T; 24]>, <P as PtrTc<[T; 24]>>::Pointer: PointerMut + Deref<Target=[T; 24]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 24]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
+impl<T, U, const N: usize> ArrTc<U> for [T; N] {
+    type Arr = [U; N];
+    type Kind = [U; N];
+    type Source = [T; N];
 }
 
-unsafe impl<T> CastArrHelper for ([T; 25], [T; 25]) {
-    type From = [T; 25];
-    type To = [T; 25];
+/// Initialization type constructor
+///
+/// For a possibly uninitialized type it constructs an initialied version of the type.
+pub trait InitTc<Item>: ArrTc<Item> {
+    /// Resulting type
+    type Output: Sized;
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+    /// Casts the types.
+    ///
+    /// # Safety
+    ///
+    /// All items must be initialized.
+    unsafe fn cast(self) -> Self::Output;
 }
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 25], [T; 25]) {
-    type From = [MaybeUninit<T>; 25];
-    type To = [T; 25];
+/// Key used to look up the `CastArrHelper` impl for given type.
+type CastKey<T, Item> = (T, PhantomData<<T as ArrTc<Item>>::Source>, <T as ArrTc<Item>>::Kind);
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 25]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 25]> + PtrTc<[T; 25]>, <P as PtrTc<[T; 25]>>::Pointer: PointerMut + Deref<Target=[T; 25]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 25]>>::Pointer;
+impl<Item, T> InitTc<Item> for T where T: ArrTc<Item>, CastKey<T, Item>: CastArrHelper<From=Self> {
+    type Output = <CastKey<T, Item> as CastArrHelper>::To;
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
+    unsafe fn cast(self) -> Self::Output {
+        <CastKey<T, Item>>::cast(self)
     }
 }
 
-unsafe impl<T> CastArrHelper for ([T; 26], [T; 26]) {
-    type From = [T; 26];
-    type To = [T; 26];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
+/// A trick to work arround the limitation of Rust where the compiler can't understand that two
+/// impls with different associated types aren't conflicting.
+///
+/// The impls are keyed by `(From, PhantomData<Source>, Kind)` where `Source`
+/// is the dereferenced version of `From` and `Kind` is the dereferenced
+/// version of `To`. This way the casts from `MaybeUninit<T>` and `T` arrays
+/// don't conflict.
+///
+/// # Safety
+///
+/// `From` and `To` must have the same layout, differing only in
+/// initializedness of the items.
+pub unsafe trait CastArrHelper {
+    /// Type from which we are casting
+    type From: Sized;
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 26], [T; 26]) {
-    type From = [MaybeUninit<T>; 26];
-    type To = [T; 26];
+    /// Type into which we are casting
+    type To: Sized;
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+    /// Cast those two things
+    ///
+    /// # Safety
+    ///
+    /// All items must be initialized.
+    unsafe fn cast(from: Self::From) -> Self::To;
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 26]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 26]> + PtrTc<[T; 26]>, <P as PtrTc<[T; 26]>>::Pointer: PointerMut + Deref<Target=[T; 26]> {
+unsafe impl<P, T> CastArrHelper for (P, PhantomData<[MaybeUninit<T>]>, [T]) where P: SlicePointerMut<Item=MaybeUninit<T>> + Deref<Target=[MaybeUninit<T>]> + PtrTc<[T]>, <P as PtrTc<[T]>>::Pointer: SlicePointerMut<Item=T> + Deref<Target=[T]> {
     type From = P;
-    type To = <P as PtrTc<[T; 26]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 27], [T; 27]) {
-    type From = [T; 27];
-    type To = [T; 27];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 27], [T; 27]) {
-    type From = [MaybeUninit<T>; 27];
-    type To = [T; 27];
+    type To = <P as PtrTc<[T]>>::Pointer;
 
     unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
+        let (ptr, len) = from.into_raw_parts_mut();
+        Self::To::from_raw_parts_mut(ptr as *mut <Self::To as SlicePointerMut>::Item, len)
     }
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 27]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 27]> + PtrTc<[T; 27]>, <P as PtrTc<[T; 27]>>::Pointer: PointerMut + Deref<Target=[T; 27]> {
+unsafe impl<P, T> CastArrHelper for (P, PhantomData<[T]>, [T]) where P: SlicePointerMut<Item=T> + Deref<Target=[T]> {
     type From = P;
-    type To = <P as PtrTc<[T; 27]>>::Pointer;
+    type To = P;
 
     unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
+        from
     }
 }
 
-unsafe impl<T> CastArrHelper for ([T; 28], [T; 28]) {
-    type From = [T; 28];
-    type To = [T; 28];
+unsafe impl<T, const N: usize> CastArrHelper for ([T; N], PhantomData<[T; N]>, [T; N]) {
+    type From = [T; N];
+    type To = [T; N];
 
     unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
+        from
     }
 }
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 28], [T; 28]) {
-    type From = [MaybeUninit<T>; 28];
-    type To = [T; 28];
+unsafe impl<T, const N: usize> CastArrHelper for ([MaybeUninit<T>; N], PhantomData<[MaybeUninit<T>; N]>, [T; N]) {
+    type From = [MaybeUninit<T>; N];
+    type To = [T; N];
 
     unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
+        // `transmute` can't be used with generic arrays (rust-lang/rust#61956)
+        // but the types have the same layout.
+        let () = crate::zeroed::LayoutCheck::<[MaybeUninit<T>; N], [T; N]>::SAME_LAYOUT;
+        let from = ManuallyDrop::new(from);
+        core::ptr::read(&*from as *const [MaybeUninit<T>; N] as *const [T; N])
     }
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 28]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 28]> + PtrTc<[T; 28]>, <P as PtrTc<[T; 28]>>::Pointer: PointerMut + Deref<Target=[T; 28]> {
+unsafe impl<P, T, const N: usize> CastArrHelper for (P, PhantomData<[MaybeUninit<T>; N]>, [T; N]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; N]> + PtrTc<[T; N]>, <P as PtrTc<[T; N]>>::Pointer: PointerMut + Deref<Target=[T; N]> {
     type From = P;
-    type To = <P as PtrTc<[T; 28]>>::Pointer;
+    type To = <P as PtrTc<[T; N]>>::Pointer;
 
     unsafe fn cast(from: Self::From) -> Self::To {
         Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
     }
 }
 
-unsafe impl<T> CastArrHelper for ([T; 29], [T; 29]) {
-    type From = [T; 29];
-    type To = [T; 29];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 29], [T; 29]) {
-    type From = [MaybeUninit<T>; 29];
-    type To = [T; 29];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
-}
-
-unsafe impl<P, T> CastArrHelper for (P, [T; 29]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 29]> + PtrTc<[T; 29]>, <P as PtrTc<[T; 29]>>::Pointer: PointerMut + Deref<Target=[T; 29]> {
+unsafe impl<P, T, const N: usize> CastArrHelper for (P, PhantomData<[T; N]>, [T; N]) where P: PointerMut + Deref<Target=[T; N]> {
     type From = P;
-    type To = <P as PtrTc<[T; 29]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
-}
-
-unsafe impl<T> CastArrHelper for ([T; 30], [T; 30]) {
-    type From = [T; 30];
-    type To = [T; 30];
+    type To = P;
 
     unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
+        from
     }
 }
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 30], [T; 30]) {
-    type From = [MaybeUninit<T>; 30];
-    type To = [T; 30];
+unsafe impl<T> CastArrHelper for (MaybeUninit<T>, PhantomData<MaybeUninit<T>>, T) {
+    type From = MaybeUninit<T>;
+    type To = T;
 
     unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
+        from.assume_init()
     }
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 30]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 30]> + PtrTc<[T; 30]>, <P as PtrTc<[T; 30]>>::Pointer: PointerMut + Deref<Target=[T; 30]> {
+unsafe impl<P, T> CastArrHelper for (P, PhantomData<MaybeUninit<T>>, T) where P: PointerMut + Deref<Target=MaybeUninit<T>> + PtrTc<T>, <P as PtrTc<T>>::Pointer: PointerMut + Deref<Target=T> {
     type From = P;
-    type To = <P as PtrTc<[T; 30]>>::Pointer;
+    type To = <P as PtrTc<T>>::Pointer;
 
     unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
+        Self::To::from_raw_mut(from.into_raw_mut() as *mut T)
     }
 }
 
-unsafe impl<T> CastArrHelper for ([T; 31], [T; 31]) {
-    type From = [T; 31];
-    type To = [T; 31];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+/// Casts possibly uninitialized values or containers into initialized ones.
+///
+/// This is implemented for `MaybeUninit<T>`, `[MaybeUninit<T>; N]` and
+/// pointers to `MaybeUninit<T>`, `[MaybeUninit<T>]` and `[MaybeUninit<T>; N]`
+/// (`&mut`, `Box`, `Rc`, `Arc` and `Pin` of them) as well as
+/// `Vec<MaybeUninit<T>>`.
+pub trait AssumeInit: Sized {
+    /// The initialized type.
+    type Init;
+
+    /// Casts the value assuming it's initialized.
+    ///
+    /// # Panics
+    ///
+    /// Casting `Rc` or `Arc` panics if it's shared, e.g. if it was cloned.
+    /// Use `Rc::get_mut` or `Arc::get_mut` to check it beforehand.
+    ///
+    /// # Safety
+    ///
+    /// All items must be initialized.
+    unsafe fn assume_init(self) -> Self::Init;
 }
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 31], [T; 31]) {
-    type From = [MaybeUninit<T>; 31];
-    type To = [T; 31];
+macro_rules! impl_assume_init {
+    ($({$($generics:tt)*} $from:ty;)*) => {
+        $(
+            impl<$($generics)*> AssumeInit for $from {
+                type Init = <$from as InitTc<T>>::Output;
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
+                unsafe fn assume_init(self) -> Self::Init {
+                    InitTc::<T>::cast(self)
+                }
+            }
+        )*
     }
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 31]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 31]> + PtrTc<[T; 31]>, <P as PtrTc<[T; 31]>>::Pointer: PointerMut + Deref<Target=[T; 31]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 31]>>::Pointer;
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
+impl_assume_init! {
+    {T} MaybeUninit<T>;
+    {T, const N: usize} [MaybeUninit<T>; N];
+    {'a, T} &'a mut MaybeUninit<T>;
+    {'a, T} &'a mut [MaybeUninit<T>];
+    {'a, T, const N: usize} &'a mut [MaybeUninit<T>; N];
 }
 
-unsafe impl<T> CastArrHelper for ([T; 32], [T; 32]) {
-    type From = [T; 32];
-    type To = [T; 32];
-
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
-    }
+#[cfg(feature = "alloc")]
+impl_assume_init! {
+    {T} alloc::boxed::Box<MaybeUninit<T>>;
+    {T} alloc::boxed::Box<[MaybeUninit<T>]>;
+    {T, const N: usize} alloc::boxed::Box<[MaybeUninit<T>; N]>;
+    {T} alloc::rc::Rc<MaybeUninit<T>>;
+    {T} alloc::rc::Rc<[MaybeUninit<T>]>;
+    {T, const N: usize} alloc::rc::Rc<[MaybeUninit<T>; N]>;
+    {T} alloc::sync::Arc<MaybeUninit<T>>;
+    {T} alloc::sync::Arc<[MaybeUninit<T>]>;
+    {T, const N: usize} alloc::sync::Arc<[MaybeUninit<T>; N]>;
+    {T} alloc::vec::Vec<MaybeUninit<T>>;
 }
 
-unsafe impl<T> CastArrHelper for ([MaybeUninit<T>; 32], [T; 32]) {
-    type From = [MaybeUninit<T>; 32];
-    type To = [T; 32];
+impl<P> AssumeInit for Pin<P> where P: AssumeInit + Deref, P::Init: Deref {
+    type Init = Pin<P::Init>;
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        transmute_workaround_size_bug_super_dangerous_ignores_size(from)
+    unsafe fn assume_init(self) -> Self::Init {
+        Pin::new_unchecked(Pin::into_inner_unchecked(self).assume_init())
     }
 }
 
-unsafe impl<P, T> CastArrHelper for (P, [T; 32]) where P: PointerMut + Deref<Target=[MaybeUninit<T>; 32]> + PtrTc<[T; 32]>, <P as PtrTc<[T; 32]>>::Pointer: PointerMut + Deref<Target=[T; 32]> {
-    type From = P;
-    type To = <P as PtrTc<[T; 32]>>::Pointer;
+/// Casts owned containers into possibly uninitialized ones, dropping their
+/// contents.
+///
+/// This is the opposite of `AssumeInit`. It allows reusing the allocation,
+/// e.g. in buffer pools.
+pub trait Uninit: Sized {
+    /// The possibly uninitialized type.
+    type Uninit;
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        Self::To::from_raw_mut(from.into_raw_mut() as *mut <Self::To as Deref>::Target)
-    }
+    /// Drops the contents in place and casts the container.
+    ///
+    /// # Panics
+    ///
+    /// Casting `Rc` or `Arc` panics if it's shared.
+    fn uninit(self) -> Self::Uninit;
 }
 
-/*
-unsafe impl<P, T> CastArrHelper for (P, [T]) where P: SlicePointerMut<Item=T> + Deref<Target=[T]> + PtrTc<[T]>, <P as PtrTc<[T]>>::Pointer: SlicePointerMut<Item=T> + Deref<Target=[T]> {
-    type From = P;
-    type To = <P as PtrTc<[T]>>::Pointer;
+impl<P> Uninit for Pin<P> where P: Uninit + Deref, P::Uninit: Deref {
+    type Uninit = Pin<P::Uninit>;
 
-    unsafe fn cast(from: Self::From) -> Self::To {
-        let (ptr, len) = from.into_raw_parts_mut();
-        Self::To::from_raw_parts_mut(ptr as *mut <Self::To as SlicePointerMut>::Item, len)
+    fn uninit(self) -> Self::Uninit {
+        // The contents are dropped without being moved.
+        unsafe {
+            Pin::new_unchecked(Pin::into_inner_unchecked(self).uninit())
+        }
     }
 }
-*/
 
 /// Type constructor for smart pointer types
 pub trait PtrTc<T: ?Sized> {
@@ -1181,12 +338,51 @@ impl<T: ?Sized, U: ?Sized> PtrTc<U> for alloc::boxed::Box<T> {
     type Pointer = alloc::boxed::Box<U>;
 }
 
+#[cfg(feature = "alloc")]
+impl<T: ?Sized, U: ?Sized> PtrTc<U> for alloc::rc::Rc<T> {
+    type Pointer = alloc::rc::Rc<U>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized, U: ?Sized> PtrTc<U> for alloc::sync::Arc<T> {
+    type Pointer = alloc::sync::Arc<U>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T, U> PtrTc<[U]> for alloc::vec::Vec<T> {
+    type Pointer = alloc::vec::Vec<U>;
+}
+
+impl<P: PtrTc<U>, U: ?Sized> PtrTc<U> for Pin<P> {
+    type Pointer = Pin<<P as PtrTc<U>>::Pointer>;
+}
+
 /// Trait for unique smart pointers/references containing `Sized` types
 /// allowing casting between them.
-pub unsafe trait PointerMut: Sized + StableDeref + DerefMut where <Self as Deref>::Target: Sized {
+///
+/// Shared pointers (`Rc`, `Arc`) implement it too, but they panic in
+/// `into_raw_mut` if they are not unique.
+///
+/// # Safety
+///
+/// The pointer returned from `into_raw_mut` must be the only pointer through
+/// which the target can be accessed for as long as the returned smart pointer
+/// (or the one created by `from_raw_mut`) lives. In other words, the
+/// implementor must be unique at the time of the cast, just like `&mut T`,
+/// even though it doesn't have to implement `DerefMut`. Implementations for
+/// shared pointers must check it and panic if it's not the case.
+///
+/// See also the documentation of `from_raw_mut`.
+pub unsafe trait PointerMut: Sized + StableDeref where <Self as Deref>::Target: Sized {
     /// Converts the smart pointer into raw pointer.
     ///
     /// The original smart pointer must be forgotten.
+    ///
+    /// # Panics
+    ///
+    /// The implementations for `Rc` and `Arc` panic if the pointer is shared,
+    /// e.g. if it was cloned. Use `Rc::get_mut` or `Arc::get_mut` to check it
+    /// beforehand.
     fn into_raw_mut(self) -> *mut Self::Target;
 
     /// Converts a raw pointer returned from `into_raw_mut` into smart pointer.
@@ -1215,11 +411,32 @@ unsafe impl<T> PointerMut for &mut T {
 
 /// Trait for unique smart pointers/references containing slices, allowing
 /// casting between them.
-pub unsafe trait SlicePointerMut: Sized + StableDeref + Deref<Target=[<Self as SlicePointerMut>::Item]> + DerefMut {
+///
+/// Shared pointers (`Rc`, `Arc`) implement it too, but they panic in
+/// `into_raw_parts_mut` if they are not unique.
+///
+/// # Safety
+///
+/// The pointer returned from `into_raw_parts_mut` must be the only pointer
+/// through which the slice can be accessed for as long as the returned smart
+/// pointer (or the one created by `from_raw_parts_mut`) lives. In other
+/// words, the implementor must be unique at the time of the cast, just like
+/// `&mut [T]`, even though it doesn't have to implement `DerefMut`.
+/// Implementations for shared pointers must check it and panic if it's not
+/// the case.
+///
+/// See also the documentation of `from_raw_parts_mut`.
+pub unsafe trait SlicePointerMut: Sized + StableDeref + Deref<Target=[<Self as SlicePointerMut>::Item]> {
     /// Type of contained item.
     type Item: Sized;
 
     /// Returns pointer and length of the slice, forgetting the original value.
+    ///
+    /// # Panics
+    ///
+    /// The implementations for `Rc` and `Arc` panic if the pointer is shared,
+    /// e.g. if it was cloned. Use `Rc::get_mut` or `Arc::get_mut` to check it
+    /// beforehand.
     fn into_raw_parts_mut(self) -> (*mut Self::Item, usize);
 
     /// Converts a raw pointer and length returned from `into_raw_mut` into
@@ -1249,9 +466,39 @@ unsafe impl<T> SlicePointerMut for &mut [T] {
     }
 }
 
+// Pinned data is never moved by the casts, so the pointers may be repinned.
+unsafe impl<P: PointerMut> PointerMut for Pin<P> where P::Target: Sized {
+    fn into_raw_mut(self) -> *mut Self::Target {
+        unsafe {
+            Pin::into_inner_unchecked(self).into_raw_mut()
+        }
+    }
+
+    unsafe fn from_raw_mut(ptr: *mut Self::Target) -> Self {
+        Pin::new_unchecked(P::from_raw_mut(ptr))
+    }
+}
+
+unsafe impl<P: SlicePointerMut> SlicePointerMut for Pin<P> {
+    type Item = P::Item;
+
+    fn into_raw_parts_mut(self) -> (*mut Self::Item, usize) {
+        unsafe {
+            Pin::into_inner_unchecked(self).into_raw_parts_mut()
+        }
+    }
+
+    unsafe fn from_raw_parts_mut(ptr: *mut Self::Item, len: usize) -> Self {
+        Pin::new_unchecked(P::from_raw_parts_mut(ptr, len))
+    }
+}
+
 #[cfg(feature = "alloc")]
 mod alloc_impls {
     use alloc::boxed::Box;
+    use alloc::rc::Rc;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
     use super::*;
 
     unsafe impl<T> PointerMut for Box<T> {
@@ -1277,7 +524,377 @@ mod alloc_impls {
         }
 
         unsafe fn from_raw_parts_mut(ptr: *mut Self::Item, len: usize) -> Self {
-            Box::from_raw(core::slice::from_raw_parts_mut(ptr, len))
+            Box::from_raw(core::ptr::slice_from_raw_parts_mut(ptr, len))
+        }
+    }
+
+    // `Vec` doesn't implement `SlicePointerMut`, because its capacity would
+    // be lost, so it has its own `CastArrHelper` impls preserving it.
+    unsafe impl<T> CastArrHelper for (Vec<MaybeUninit<T>>, PhantomData<[MaybeUninit<T>]>, [T]) {
+        type From = Vec<MaybeUninit<T>>;
+        type To = Vec<T>;
+
+        unsafe fn cast(from: Self::From) -> Self::To {
+            let mut vec = ManuallyDrop::new(from);
+            Vec::from_raw_parts(vec.as_mut_ptr() as *mut T, vec.len(), vec.capacity())
+        }
+    }
+
+    unsafe impl<T> CastArrHelper for (Vec<T>, PhantomData<[T]>, [T]) {
+        type From = Vec<T>;
+        type To = Vec<T>;
+
+        unsafe fn cast(from: Self::From) -> Self::To {
+            from
+        }
+    }
+
+    macro_rules! impl_shared {
+        ($($ptr:ident),*) => {
+            $(
+                unsafe impl<T> PointerMut for $ptr<T> {
+                    fn into_raw_mut(mut self) -> *mut Self::Target {
+                        assert!($ptr::get_mut(&mut self).is_some(), concat!("attempt to cast shared ", stringify!($ptr)));
+                        $ptr::into_raw(self) as *mut T
+                    }
+
+                    unsafe fn from_raw_mut(ptr: *mut Self::Target) -> Self {
+                        $ptr::from_raw(ptr)
+                    }
+                }
+
+                unsafe impl<T> SlicePointerMut for $ptr<[T]> {
+                    type Item=T;
+
+                    fn into_raw_parts_mut(mut self) -> (*mut Self::Item, usize) {
+                        assert!($ptr::get_mut(&mut self).is_some(), concat!("attempt to cast shared ", stringify!($ptr)));
+                        let len = self.len();
+                        ($ptr::into_raw(self) as *mut T, len)
+                    }
+
+                    unsafe fn from_raw_parts_mut(ptr: *mut Self::Item, len: usize) -> Self {
+                        $ptr::from_raw(core::ptr::slice_from_raw_parts_mut(ptr, len))
+                    }
+                }
+
+                impl<T> Uninit for $ptr<T> {
+                    type Uninit = $ptr<MaybeUninit<T>>;
+
+                    fn uninit(self) -> Self::Uninit {
+                        let ptr = self.into_raw_mut();
+                        unsafe {
+                            core::ptr::drop_in_place(ptr);
+                            $ptr::from_raw(ptr as *mut MaybeUninit<T>)
+                        }
+                    }
+                }
+
+                impl<T> Uninit for $ptr<[T]> {
+                    type Uninit = $ptr<[MaybeUninit<T>]>;
+
+                    fn uninit(self) -> Self::Uninit {
+                        let (ptr, len) = self.into_raw_parts_mut();
+                        unsafe {
+                            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(ptr, len));
+                            $ptr::from_raw_parts_mut(ptr as *mut MaybeUninit<T>, len)
+                        }
+                    }
+                }
+            )*
+        }
+    }
+
+    impl_shared!(Rc, Arc);
+
+    impl<T> Uninit for Box<T> {
+        type Uninit = Box<MaybeUninit<T>>;
+
+        fn uninit(self) -> Self::Uninit {
+            let ptr = Box::into_raw(self);
+            unsafe {
+                core::ptr::drop_in_place(ptr);
+                Box::from_raw(ptr as *mut MaybeUninit<T>)
+            }
+        }
+    }
+
+    impl<T> Uninit for Box<[T]> {
+        type Uninit = Box<[MaybeUninit<T>]>;
+
+        fn uninit(self) -> Self::Uninit {
+            let (ptr, len) = self.into_raw_parts_mut();
+            unsafe {
+                core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(ptr, len));
+                Box::from_raw_parts_mut(ptr as *mut MaybeUninit<T>, len)
+            }
+        }
+    }
+
+    /// The length and capacity are preserved.
+    impl<T> Uninit for Vec<T> {
+        type Uninit = Vec<MaybeUninit<T>>;
+
+        fn uninit(self) -> Self::Uninit {
+            let mut vec = core::mem::ManuallyDrop::new(self);
+            let ptr = vec.as_mut_ptr();
+            let len = vec.len();
+            let capacity = vec.capacity();
+            unsafe {
+                core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(ptr, len));
+                Vec::from_raw_parts(ptr as *mut MaybeUninit<T>, len, capacity)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Each test covers one `CastArrHelper` impl with all pointer types.
+    // The items are `String`s if possible, so that Miri detects leaks and
+    // double drops.
+
+    #[cfg(feature = "alloc")]
+    use alloc::{boxed::Box, rc::Rc, sync::Arc, string::String, vec::Vec};
+
+    #[cfg(feature = "alloc")]
+    fn strings() -> [MaybeUninit<String>; 2] {
+        [MaybeUninit::new(String::from("hello")), MaybeUninit::new(String::from("world"))]
+    }
+
+    #[test]
+    fn init_array() {
+        let array: [u8; 2] = unsafe { InitTc::<u8>::cast([1u8, 2]) };
+        assert_eq!(array, [1, 2]);
+    }
+
+    #[test]
+    fn uninit_array() {
+        let array: [u8; 2] = unsafe { InitTc::<u8>::cast([MaybeUninit::new(1u8), MaybeUninit::new(2)]) };
+        assert_eq!(array, [1, 2]);
+        let empty: [MaybeUninit<u8>; 0] = [];
+        let array: [u8; 0] = unsafe { InitTc::<u8>::cast(empty) };
+        assert_eq!(array, []);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn uninit_array_strings() {
+        let array: [String; 2] = unsafe { InitTc::<String>::cast(strings()) };
+        assert_eq!(array, ["hello", "world"]);
+    }
+
+    #[test]
+    fn init_array_pointers() {
+        let mut array = [1u8, 2];
+        let array: &mut [u8; 2] = unsafe { InitTc::<u8>::cast(&mut array) };
+        assert_eq!(*array, [1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn init_array_pointers_alloc() {
+        let array: Box<[String; 1]> = unsafe { InitTc::<String>::cast(Box::new([String::from("hello")])) };
+        assert_eq!(*array, ["hello"]);
+    }
+
+    #[test]
+    fn uninit_array_pointers() {
+        let mut array = [MaybeUninit::new(1u8), MaybeUninit::new(2)];
+        let array: &mut [u8; 2] = unsafe { InitTc::<u8>::cast(&mut array) };
+        assert_eq!(*array, [1, 2]);
+
+        let mut array = [MaybeUninit::new(1u8), MaybeUninit::new(2)];
+        let array: Pin<&mut [u8; 2]> = unsafe { InitTc::<u8>::cast(Pin::new(&mut array)) };
+        assert_eq!(*array, [1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn uninit_array_pointers_alloc() {
+        let array: Box<[String; 2]> = unsafe { InitTc::<String>::cast(Box::new(strings())) };
+        assert_eq!(*array, ["hello", "world"]);
+        let array: Pin<Box<[String; 2]>> = unsafe { InitTc::<String>::cast(Box::pin(strings())) };
+        assert_eq!(*array, ["hello", "world"]);
+        let array: Rc<[String; 2]> = unsafe { InitTc::<String>::cast(Rc::new(strings())) };
+        assert_eq!(*array, ["hello", "world"]);
+        let array: Arc<[String; 2]> = unsafe { InitTc::<String>::cast(Arc::new(strings())) };
+        assert_eq!(*array, ["hello", "world"]);
+    }
+
+    #[test]
+    fn init_slice_pointers() {
+        let mut array = [1u8, 2];
+        let slice: &mut [u8] = unsafe { InitTc::<u8>::cast(&mut array[..]) };
+        assert_eq!(slice, [1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn init_slice_pointers_alloc() {
+        let slice: Box<[String]> = Box::new([String::from("hello")]);
+        let slice: Box<[String]> = unsafe { InitTc::<String>::cast(slice) };
+        assert_eq!(*slice, ["hello"]);
+        let vec: Vec<String> = unsafe { InitTc::<String>::cast(alloc::vec![String::from("hello")]) };
+        assert_eq!(vec, ["hello"]);
+    }
+
+    #[test]
+    fn uninit_slice_pointers() {
+        let mut array = [MaybeUninit::new(1u8), MaybeUninit::new(2)];
+        let slice: &mut [u8] = unsafe { InitTc::<u8>::cast(&mut array[..]) };
+        assert_eq!(slice, [1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn uninit_slice_pointers_alloc() {
+        let slice: Box<[_]> = Box::new(strings());
+        let slice: Box<[String]> = unsafe { InitTc::<String>::cast(slice) };
+        assert_eq!(*slice, ["hello", "world"]);
+        let slice: Pin<Box<[_]>> = Box::into_pin(Box::new(strings()) as Box<[_]>);
+        let slice: Pin<Box<[String]>> = unsafe { InitTc::<String>::cast(slice) };
+        assert_eq!(*slice, ["hello", "world"]);
+        let slice: Rc<[_]> = Rc::new(strings());
+        let slice: Rc<[String]> = unsafe { InitTc::<String>::cast(slice) };
+        assert_eq!(*slice, ["hello", "world"]);
+        let slice: Arc<[_]> = Arc::new(strings());
+        let slice: Arc<[String]> = unsafe { InitTc::<String>::cast(slice) };
+        assert_eq!(*slice, ["hello", "world"]);
+
+        let mut vec = Vec::with_capacity(4);
+        vec.extend(strings());
+        let ptr = vec.as_ptr() as *const String;
+        let vec: Vec<String> = unsafe { InitTc::<String>::cast(vec) };
+        assert_eq!(vec, ["hello", "world"]);
+        assert_eq!(vec.capacity(), 4);
+        assert_eq!(vec.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn uninit_value() {
+        let value: u8 = unsafe { InitTc::<u8>::cast(MaybeUninit::new(42u8)) };
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn uninit_value_pointers() {
+        let mut value = MaybeUninit::new(42u8);
+        let value: &mut u8 = unsafe { InitTc::<u8>::cast(&mut value) };
+        assert_eq!(*value, 42);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn uninit_value_pointers_alloc() {
+        let value = || MaybeUninit::new(String::from("hello"));
+        let boxed: Box<String> = unsafe { InitTc::<String>::cast(Box::new(value())) };
+        assert_eq!(*boxed, "hello");
+        let boxed: Pin<Box<String>> = unsafe { InitTc::<String>::cast(Box::pin(value())) };
+        assert_eq!(*boxed, "hello");
+        let rc: Rc<String> = unsafe { InitTc::<String>::cast(Rc::new(value())) };
+        assert_eq!(*rc, "hello");
+        let arc: Arc<String> = unsafe { InitTc::<String>::cast(Arc::new(value())) };
+        assert_eq!(*arc, "hello");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic]
+    fn shared_not_unique() {
+        let rc = Rc::new(MaybeUninit::new(42u32));
+        let _clone = rc.clone();
+        unsafe {
+            InitTc::<u32>::cast(rc);
         }
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn cursor() {
+        let storage: Box<[MaybeUninit<String>]> = Box::new([MaybeUninit::uninit(), MaybeUninit::uninit()]);
+        let mut cursor = crate::slice::Cursor::new(storage);
+        cursor.push(String::from("hi")).expect("cursor full");
+        let mut cursor = cursor.try_cast_initialized().unwrap_err();
+        cursor.push(String::from("there")).expect("cursor full");
+        let slice: Box<[String]> = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("not initialized"));
+        assert_eq!(*slice, ["hi", "there"]);
+    }
+
+    #[test]
+    fn assume_init() {
+        let value = unsafe { AssumeInit::assume_init(MaybeUninit::new(42u8)) };
+        assert_eq!(value, 42);
+        let array = unsafe { AssumeInit::assume_init([MaybeUninit::new(1u8), MaybeUninit::new(2)]) };
+        assert_eq!(array, [1, 2]);
+        let mut array = [MaybeUninit::new(1u8), MaybeUninit::new(2)];
+        let slice = unsafe { AssumeInit::assume_init(&mut array[..]) };
+        assert_eq!(slice, [1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn uninit() {
+        use alloc::boxed::Box;
+        use alloc::vec::Vec;
+        use core::cell::Cell;
+        use crate::test_util::DropCounter;
+
+        let counter = Cell::new(0);
+        let mut boxed = Box::new(DropCounter(&counter)).uninit();
+        assert_eq!(counter.get(), 1);
+        *boxed = MaybeUninit::new(DropCounter(&counter));
+        drop(unsafe { AssumeInit::assume_init(boxed) });
+        assert_eq!(counter.get(), 2);
+
+        let slice: Box<[_]> = Box::new([DropCounter(&counter), DropCounter(&counter)]);
+        let mut slice = slice.uninit();
+        assert_eq!(counter.get(), 4);
+        slice[0] = MaybeUninit::new(DropCounter(&counter));
+        slice[1] = MaybeUninit::new(DropCounter(&counter));
+        drop(unsafe { AssumeInit::assume_init(slice) });
+        assert_eq!(counter.get(), 6);
+
+        let mut vec = Vec::with_capacity(4);
+        vec.push(DropCounter(&counter));
+        let vec = vec.uninit();
+        assert_eq!(counter.get(), 7);
+        assert_eq!(vec.len(), 1);
+        assert_eq!(vec.capacity(), 4);
+
+        let pinned = Box::pin(DropCounter(&counter)).uninit();
+        assert_eq!(counter.get(), 8);
+        drop(pinned);
+        assert_eq!(counter.get(), 8);
+
+        let rc = Rc::new(DropCounter(&counter)).uninit();
+        assert_eq!(counter.get(), 9);
+        drop(rc);
+        assert_eq!(counter.get(), 9);
+
+        let rc: Rc<[_]> = Rc::new([DropCounter(&counter), DropCounter(&counter)]);
+        let mut rc = rc.uninit();
+        assert_eq!(counter.get(), 11);
+        Rc::get_mut(&mut rc).expect("shared")[0] = MaybeUninit::new(DropCounter(&counter));
+        Rc::get_mut(&mut rc).expect("shared")[1] = MaybeUninit::new(DropCounter(&counter));
+        drop(unsafe { AssumeInit::assume_init(rc) });
+        assert_eq!(counter.get(), 13);
+
+        // `Arc` of `DropCounter` would trigger `clippy::arc_with_non_send_sync`.
+        let mut arc = Arc::new(String::from("hello")).uninit();
+        *Arc::get_mut(&mut arc).expect("shared") = MaybeUninit::new(String::from("world"));
+        assert_eq!(*unsafe { AssumeInit::assume_init(arc) }, "world");
+        let arc: Arc<[String]> = Arc::new([String::from("hello")]);
+        assert_eq!(arc.uninit().len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic]
+    fn uninit_shared() {
+        let rc = Rc::new(String::from("hello"));
+        let _clone = rc.clone();
+        rc.uninit();
+    }
 }
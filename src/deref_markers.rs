@@ -10,6 +10,11 @@ use core::ops::Deref;
 /// fixed location.
 ///
 /// It implies `SameDataRef`
+///
+/// # Safety
+///
+/// The address returned from `deref` must stay the same for the whole lifetime
+/// of the value, even if the value is moved.
 pub unsafe trait StableDeref: SameDataDeref {}
 
 unsafe impl<T: StableDeref> SameDataDeref for T {}
@@ -21,6 +26,11 @@ unsafe impl<T: StableDeref> SameDataDeref for T {}
 /// return a different refernce each time it's called. It'd be very surprising
 /// if anyone actually did that, but nevertheless, `unsafe` code must *not* rely
 /// on it. Thus this `unsafe` marker ensuring it's not happening.
+///
+/// # Safety
+///
+/// `deref` (and `deref_mut`, if implemented) must always return a reference to
+/// the same data.
 pub unsafe trait SameDataDeref: Deref {}
 
 unsafe impl<T: ?Sized> StableDeref for &T {}
@@ -28,6 +38,7 @@ unsafe impl<T: ?Sized> StableDeref for &mut T {}
 unsafe impl<T: ?Sized> StableDeref for core::cell::Ref<'_, T> {}
 unsafe impl<T: ?Sized> StableDeref for core::cell::RefMut<'_, T> {}
 unsafe impl<T: ?Sized> SameDataDeref for core::mem::ManuallyDrop<T> {}
+unsafe impl<P: StableDeref> StableDeref for core::pin::Pin<P> {}
 
 #[cfg(feature = "alloc")]
 mod alloc_impls {
@@ -0,0 +1,257 @@
+//! Helpers for reading into uninitialized buffers.
+//!
+//! `std::io::Read::read` takes `&mut [u8]`, so normally the buffer has to be
+//! zeroed before reading into it, since passing uninitialized buffer to `read`
+//! is undefined behavior. That's wasteful, so readers implementing
+//! `UninitRead` can read into `OutSlice` directly instead.
+//!
+//! Unknown readers can still be used with `*_zeroing` functions, which zero
+//! the buffer first.
+
+use std::io::{self, BufRead, Read};
+use crate::slice::{BorrowOutSlice, OutSlice};
+
+/// Readers that can read into possibly uninitialized buffer.
+///
+/// Calling `Read::read` with uninitialized buffer is undefined behavior even
+/// if the reader doesn't read from it, because it creates `&mut [u8]`
+/// pointing to uninitialized bytes. This trait provides a method reading into
+/// `OutSlice` instead.
+///
+/// # Safety
+///
+/// If `read_uninit` returns `Ok(n)`, then `n <= buf.len()` and first `n`
+/// bytes of the buffer must be initialized.
+pub unsafe trait UninitRead: Read {
+    /// Reads into possibly uninitialized buffer returning the number of
+    /// bytes written at the beginning of the buffer.
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize>;
+}
+
+/// Copies the data from the buffer of the reader.
+fn read_buffered<R: BufRead + ?Sized>(reader: &mut R, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+    let data = reader.fill_buf()?;
+    let len = data.len().min(buf.len());
+    buf[..len].copy_from_slice(&data[..len]);
+    reader.consume(len);
+    Ok(len)
+}
+
+/// Reads from the file descriptor using `read(2)`, which accepts raw pointer.
+#[cfg(unix)]
+fn read_fd(fd: std::os::unix::io::RawFd, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+    use std::os::raw::{c_int, c_void};
+
+    extern "C" {
+        fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+    }
+
+    // The same limits as std uses: larger reads fail with `EINVAL` on macOS.
+    #[cfg(target_vendor = "apple")]
+    const READ_LIMIT: usize = c_int::MAX as usize - 1;
+    #[cfg(not(target_vendor = "apple"))]
+    const READ_LIMIT: usize = isize::MAX as usize;
+
+    let len = buf.len().min(READ_LIMIT);
+    let result = unsafe { read(fd, buf.as_mut_ptr() as *mut c_void, len) };
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result as usize)
+    }
+}
+
+macro_rules! impl_fd {
+    ($($reader:ty),* $(,)?) => {
+        $(
+            #[cfg(unix)]
+            unsafe impl UninitRead for $reader {
+                fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+                    use std::os::unix::io::AsRawFd;
+
+                    read_fd(self.as_raw_fd(), buf)
+                }
+            }
+        )*
+    }
+}
+
+impl_fd! {
+    std::fs::File,
+    &std::fs::File,
+    std::net::TcpStream,
+    &std::net::TcpStream,
+    std::os::unix::net::UnixStream,
+    &std::os::unix::net::UnixStream,
+    std::process::ChildStdout,
+    std::process::ChildStderr,
+}
+
+unsafe impl UninitRead for io::Stdin {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        read_buffered(&mut self.lock(), buf)
+    }
+}
+
+unsafe impl UninitRead for io::StdinLock<'_> {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        read_buffered(self, buf)
+    }
+}
+
+unsafe impl UninitRead for io::Empty {
+    fn read_uninit(&mut self, _buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        Ok(0)
+    }
+}
+
+unsafe impl UninitRead for io::Repeat {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        let mut byte = [0];
+        self.read_exact(&mut byte)?;
+        Ok(buf.fill(byte[0]).len())
+    }
+}
+
+unsafe impl UninitRead for &[u8] {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        read_buffered(self, buf)
+    }
+}
+
+unsafe impl<T: AsRef<[u8]>> UninitRead for io::Cursor<T> {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        read_buffered(self, buf)
+    }
+}
+
+/// The internal buffer of `BufReader` is initialized, so the inner reader
+/// doesn't have to implement `UninitRead`.
+unsafe impl<R: Read> UninitRead for io::BufReader<R> {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        read_buffered(self, buf)
+    }
+}
+
+unsafe impl<R: UninitRead> UninitRead for io::Take<R> {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        let limit = self.limit();
+        let max = buf.len().min(core::convert::TryFrom::try_from(limit).unwrap_or(usize::MAX));
+        let len = self.get_mut().read_uninit(&mut buf[..max])?;
+        assert!(len <= max, "the reader returned more bytes than requested");
+        self.set_limit(limit - len as u64);
+        Ok(len)
+    }
+}
+
+/// `io::Chain` doesn't expose whether the first reader is done, so unlike
+/// `Read::read`, this calls the first reader again on every read, even after
+/// it returned 0 bytes. The second reader is only used if the first one
+/// returns 0. Readers that may produce more data after returning 0 (e.g. a
+/// terminal or a growing file) can thus be read from again.
+unsafe impl<A: UninitRead, B: UninitRead> UninitRead for io::Chain<A, B> {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        let (first, second) = self.get_mut();
+        match first.read_uninit(buf)? {
+            0 if !buf.is_empty() => second.read_uninit(buf),
+            len => Ok(len),
+        }
+    }
+}
+
+unsafe impl<R: UninitRead + ?Sized> UninitRead for &mut R {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        (**self).read_uninit(buf)
+    }
+}
+
+unsafe impl<R: UninitRead + ?Sized> UninitRead for std::boxed::Box<R> {
+    fn read_uninit(&mut self, buf: &mut OutSlice<u8>) -> io::Result<usize> {
+        (**self).read_uninit(buf)
+    }
+}
+
+/// Reads from the reader into possibly uninitialized buffer without zeroing
+/// it.
+///
+/// Returns the initialized part of the buffer.
+pub fn read_into<'a, R: UninitRead + ?Sized>(reader: &mut R, buf: &'a mut OutSlice<u8>) -> io::Result<&'a mut [u8]> {
+    let len = reader.read_uninit(buf)?;
+    unsafe {
+        // Guaranteed by `UninitRead`.
+        Ok(buf[..len].assume_init_mut())
+    }
+}
+
+/// Reads from an arbitrary reader into possibly uninitialized buffer, zeroing
+/// it first if needed.
+///
+/// Returns the initialized part of the buffer.
+///
+/// # Panics
+///
+/// This function panics if the reader returns greater length than the length
+/// of the buffer.
+pub fn read_into_zeroing<'a, R: Read + ?Sized>(reader: &mut R, buf: &'a mut OutSlice<u8>) -> io::Result<&'a mut [u8]> {
+    let buf = buf.zero_if_needed();
+    let len = reader.read(buf)?;
+    Ok(&mut buf[..len])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::mem::MaybeUninit;
+
+    struct Peeking;
+
+    impl Read for Peeking {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let sum = buf.iter().map(|x| u32::from(*x)).sum::<u32>();
+            buf[0] = 42;
+            buf[1] = sum as u8;
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn read_into_uninit() {
+        let mut uninit = [MaybeUninit::uninit(); 8];
+        let mut reader = &b"hello"[..];
+        assert_eq!(read_into(&mut reader, uninit.borrow_out_slice()).unwrap(), b"hello");
+        assert_eq!(read_into(&mut reader, uninit.borrow_out_slice()).unwrap(), b"");
+    }
+
+    #[test]
+    fn read_into_adapters() {
+        let mut uninit = [MaybeUninit::uninit(); 8];
+        let mut reader = Read::chain(&b"ab"[..], &b"cd"[..]).take(3);
+        assert_eq!(read_into(&mut reader, uninit.borrow_out_slice()).unwrap(), b"ab");
+        assert_eq!(read_into(&mut reader, uninit.borrow_out_slice()).unwrap(), b"c");
+        assert_eq!(read_into(&mut reader, uninit.borrow_out_slice()).unwrap(), b"");
+        assert_eq!(read_into(&mut io::repeat(7), uninit.borrow_out_slice()).unwrap(), &[7; 8]);
+        let mut reader = io::BufReader::new(Peeking);
+        assert_eq!(read_into(&mut reader, uninit.borrow_out_slice()).unwrap()[0], 42);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn read_into_file() {
+        use std::io::{Seek, SeekFrom, Write};
+
+        let path = std::env::temp_dir().join(std::format!("possibly_uninit_read_into_{}", std::process::id()));
+        let mut file = std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        file.write_all(b"hello").unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut uninit = [MaybeUninit::uninit(); 8];
+        assert_eq!(read_into(&mut file, uninit.borrow_out_slice()).unwrap(), b"hello");
+        assert_eq!(read_into(&mut file, uninit.borrow_out_slice()).unwrap(), b"");
+    }
+
+    #[test]
+    fn read_into_zeroed() {
+        let mut uninit = [MaybeUninit::uninit(); 8];
+        assert_eq!(read_into_zeroing(&mut Peeking, uninit.borrow_out_slice()).unwrap(), &[42, 0]);
+    }
+}
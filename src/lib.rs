@@ -27,9 +27,21 @@ pub mod deref_markers;
 mod borrow;
 pub mod zeroed;
 pub mod slice;
+pub mod array;
 #[cfg(feature = "alloc")]
 pub mod boxed;
+#[cfg(feature = "alloc")]
+pub mod rc;
+#[cfg(feature = "alloc")]
+pub mod sync;
+#[cfg(feature = "alloc")]
+pub mod vec;
 pub mod cast;
+pub mod project;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 use core::mem::MaybeUninit;
 use core::ptr::NonNull;
@@ -123,6 +135,27 @@ impl<'a, T> Out<'a, T> {
         }
     }
 
+    /// Splits the reference into a builder initializing the fields one by
+    /// one.
+    ///
+    /// See the `project` module.
+    pub fn project(self) -> T::Builder<'a> where T: project::OutProject {
+        T::project_out(self)
+    }
+
+    /// Overwrites the value with all zeroes in a way that can't be optimized
+    /// out.
+    ///
+    /// This is useful for wiping secrets. See `ZeroValid::secure_zero`.
+    pub fn secure_zero(&mut self) -> &mut T where T: zeroed::ZeroValid {
+        use crate::zeroed::ZeroValid;
+
+        unsafe {
+            self.0.secure_zero();
+            self.0.assume_init_mut()
+        }
+    }
+
     /// Overwrites the value with all zeroes.
     /// 
     /// Consumes the referenceto to preserve the lifetime
@@ -136,6 +169,44 @@ impl<'a, T> Out<'a, T> {
     }
 }
 
+impl<'a, T, const N: usize> Out<'a, [T; N]> {
+    /// Converts the reference to the array into `OutSlice`, so that the items
+    /// can be initialized one by one.
+    pub fn into_out_slice(self) -> &'a mut slice::OutSlice<T> {
+        let ptr = self.0.as_mut_ptr() as *mut MaybeUninit<T>;
+        unsafe {
+            core::slice::from_raw_parts_mut(ptr, N).into()
+        }
+    }
+}
+
+impl<'a, const N: usize> Out<'a, [u8; N]> {
+    /// Reinterprets the bytes as `T`.
+    ///
+    /// Fails to compile if `N` is not the size of `T`. Returns the original
+    /// reference back if it's not aligned for `T`.
+    ///
+    /// ```compile_fail
+    /// use core::mem::MaybeUninit;
+    /// use possibly_uninit::Out;
+    ///
+    /// let mut bytes = MaybeUninit::<[u8; 3]>::uninit();
+    /// let _ = Out::from(&mut bytes).cast::<u32>();
+    /// ```
+    pub fn cast<T: zeroed::AnyValid>(mut self) -> Result<Out<'a, T>, Self> {
+        let () = zeroed::SizeCheck::<T, N>::SAME_SIZE;
+
+        let ptr = self.as_mut_ptr();
+        if !zeroed::is_aligned_for::<T>(ptr as *const u8) {
+            return Err(self);
+        }
+
+        unsafe {
+            Ok(Out(&mut *(ptr as *mut MaybeUninit<T>)))
+        }
+    }
+}
+
 impl<'a, T> From<&'a mut T> for Out<'a, T> {
     fn from(value: &'a mut T) -> Self {
         unsafe {
@@ -0,0 +1,76 @@
+//! Field projection of `Out`.
+//!
+//! This allows initializing structs field by field in place, e.g. inside
+//! `Box<MaybeUninit<T>>`. The projection is usually implemented using
+//! `#[derive(OutProject)]` (requires the `derive` feature), which generates a
+//! typestate builder. The builder has a method for each field and the
+//! `finish` method is only available after all fields were written.
+//!
+//! The fields that were written are not dropped if the builder is dropped
+//! before calling `finish`.
+
+use core::marker::PhantomData;
+use crate::Out;
+
+#[cfg(feature = "derive")]
+pub use possibly_uninit_derive::OutProject;
+
+/// Typestate marker of a field that wasn't written yet.
+pub enum Unset {}
+
+/// Typestate marker of a field that was written.
+pub enum Set {}
+
+/// Types that can be initialized field by field through `Out`.
+pub trait OutProject {
+    /// The builder initializing the fields.
+    type Builder<'a> where Self: 'a;
+
+    /// Creates the builder writing into `out`.
+    fn project_out(out: Out<'_, Self>) -> Self::Builder<'_> where Self: Sized;
+}
+
+
+/// The state of a generated builder: the `Out` being initialized together with
+/// the typestate of its fields.
+///
+/// This is an implementation detail of `#[derive(OutProject)]`. The fields are
+/// private and the value can only be created or converted to another
+/// typestate using `unsafe` methods, so safe code can't forge a builder with
+/// fields marked as `Set` that weren't written.
+#[doc(hidden)]
+pub struct Projection<'a, T, S> {
+    out: Out<'a, T>,
+    _state: PhantomData<S>,
+}
+
+#[doc(hidden)]
+impl<'a, T, S> Projection<'a, T, S> {
+    /// # Safety
+    ///
+    /// All fields marked as `Set` in `S` must be initialized.
+    pub unsafe fn new(out: Out<'a, T>) -> Self {
+        Projection {
+            out,
+            _state: PhantomData,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// All fields marked as `Set` in `S2` must be initialized.
+    pub unsafe fn into_state<S2>(self) -> Projection<'a, T, S2> {
+        Projection::new(self.out)
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.out.as_mut_ptr()
+    }
+
+    /// # Safety
+    ///
+    /// All fields must be initialized.
+    pub unsafe fn into_assume_init(self) -> &'a mut T {
+        self.out.into_assume_init()
+    }
+}
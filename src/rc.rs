@@ -0,0 +1,18 @@
+//! Helpers for using `Rc`.
+
+use alloc::rc::Rc;
+
+crate::boxed::shared_ext!(Rc, RcExt);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn constructors() {
+        assert_eq!(*Rc::new_with(|| 42), 42);
+        assert_eq!(*Rc::<[u8]>::new_with_len(3, |out| out.fill(7)), [7, 7, 7]);
+        assert_eq!(*Rc::<[usize; 3]>::new_from_fn(|i| i), [0, 1, 2]);
+        assert_eq!(Rc::<[usize; 3]>::try_new_from_fn(|i| if i < 2 { Ok(0) } else { Err(i) }), Err(2));
+    }
+}
@@ -1,5 +1,9 @@
-use super::{BorrowOutSlice, OutSlice, TakeItem};
+use super::{BorrowUninitSlice, BorrowOutSlice, OutSlice, TakeItem, UninitStorage};
 use crate::cast::InitTc;
+use core::convert::TryInto;
+use core::marker::PhantomData;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut, Range};
 
 /// Wraps a (maybe uninitialized) slice as a `BorrowOutSlice`, tracking the
 /// position. This allows you to work with readers and uninitialized memory
@@ -14,15 +18,25 @@ use crate::cast::InitTc;
 /// be used where [MaybeUniniti<Item>] is expected.
 ///
 /// This looks like a huge freaking footgun that I'm unwilling to create.
-/// However I'd like to implement wrapper type in the future that will make it
-/// explicit. E.g. something like `Cursed<Written, T>`, `Cursed<Uninit, T>`,
-/// `Cursed<Entire, T>`. :) PRs welcome!
+/// Instead, the region has to be picked explicitly using the `cursed` method,
+/// which returns `Cursed<'_, Written, ..>`, `Cursed<'_, Uninit, ..>` or
+/// `Cursed<'_, Entire, ..>` wrapper implementing these traits.
 ///
-/// Important: This wrapper currently doesn't care about leaking! If you want
-/// to avoid leaks, it's recommended to use it only with buffers containing
-/// `MaybeUninit<T>` or with `Copy` types.
+/// If the underlying storage is a `MaybeUninit<Item>` buffer (e.g.
+/// `Box<[MaybeUninit<Item>]>` or `&mut [MaybeUninit<Item>; N]`) the written
+/// items are owned by the cursor and they are dropped when the cursor is
+/// dropped or reset. If the storage is `[Item]`, the items are owned by the
+/// storage, so the cursor never drops them. See
+/// `BorrowOutSlice::is_uninit_storage`.
 ///
-/// This may change in future versions.
+/// Note that items removed by `pop_slice` are *not* dropped, since the caller
+/// receives a reference to them. They are leaked in case of `MaybeUninit`
+/// storage.
+///
+/// Versions before 0.2.0 never dropped the written items. Code that keeps
+/// using a `MaybeUninit` buffer as initialized after dropping the cursor (e.g.
+/// by calling `assume_init` on it) now reads dropped values. Use
+/// `try_cast_initialized` or `into_written` to keep the items alive instead.
 pub struct Cursor<Item, Arr: BorrowOutSlice<Item> + ?Sized> {
     _phantom: core::marker::PhantomData<[Item]>,
     position: usize,
@@ -57,8 +71,12 @@ impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, Arr> {
     ///
     /// If you want to access everything that has been written so far, use the
     /// `written()` method after call to this one.
+    ///
+    /// If the iterator panics, the items written by this call are dropped in
+    /// case of `MaybeUninit` storage.
     pub fn push_iter<I>(&mut self, iter: I) -> &mut [Item] where I: IntoIterator, I::Item: TakeItem<Item> {
-        let res = self.data.borrow_out_slice()[self.position..].init_from_iter(iter);
+        let owned = self.data.is_uninit_storage();
+        let res = self.data.borrow_out_slice()[self.position..].init_from_iter_impl(iter, owned);
         self.position += res.len();
         res
     }
@@ -69,8 +87,10 @@ impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, Arr> {
     }
 
     /// Resets the position to 0.
+    ///
+    /// The written items are dropped if the storage is a `MaybeUninit` buffer.
     pub fn reset(&mut self) {
-        self.position = 0;
+        self.drop_written();
     }
 
     /// Access the written slice.
@@ -81,11 +101,16 @@ impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, Arr> {
         }
     }
 
+    /// Accesses the written slice as an array if its length is exactly `N`.
+    pub fn written_array<const N: usize>(&self) -> Option<&[Item; N]> {
+        self.written().try_into().ok()
+    }
+
     /// Mutably access the written slice.
     pub fn written_mut(&mut self) -> &mut [Item] {
         unsafe {
             let slice = &mut self.data.borrow_out_slice()[..self.position];
-            core::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut Item, slice.len())
+            core::slice::from_raw_parts_mut(slice.as_mut_ptr(), slice.len())
         }
     }
 
@@ -93,7 +118,8 @@ impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, Arr> {
     ///
     /// This can be implemented for `Copy` types safely, as swapping the place with
     /// uninitialized memory would cause corruption if the slice was actually
-    /// not MaybeUninit.
+    /// not MaybeUninit. Use `pop_owned` for non-`Copy` items in `MaybeUninit`
+    /// storage.
     pub fn pop(&mut self) -> Option<Item> where Item: Copy {
         use crate::borrow::BorrowUninit;
 
@@ -108,6 +134,28 @@ impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, Arr> {
         }
     }
 
+    /// Removes the last item and returns it.
+    ///
+    /// Unlike `pop`, this works for non-`Copy` items, but only if the storage
+    /// is statically known to be `MaybeUninit`.
+    pub fn pop_owned(&mut self) -> Option<Item> where Arr: UninitStorage<Item> {
+        debug_assert!(self.data.is_uninit_storage(), "UninitStorage must return true from is_uninit_storage");
+        // The popped items are not owned by anyone, so they can be moved out.
+        self.pop_slice(1).first().map(|item| unsafe { core::ptr::read(item) })
+    }
+
+    /// Removes up to `max` items from the end of the buffer and returns them
+    /// as an iterator.
+    ///
+    /// The items that were not consumed by the iterator are dropped when the
+    /// iterator is dropped.
+    pub fn drain_back(&mut self, max: usize) -> Drain<'_, Item> where Arr: UninitStorage<Item> {
+        debug_assert!(self.data.is_uninit_storage(), "UninitStorage must return true from is_uninit_storage");
+        Drain {
+            items: self.pop_slice(max).iter_mut(),
+        }
+    }
+
     /// "Removes" up to `max` slice from the buffer and returns them as slice.
     ///
     /// Empty slice is returned if the position is zero, of course.
@@ -116,7 +164,7 @@ impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, Arr> {
             let to_remove = self.position.min(max);
             let res = &mut self.data.borrow_out_slice()[(self.position - to_remove)..self.position];
             self.position -= to_remove;
-            core::slice::from_raw_parts_mut(res.as_mut_ptr() as *mut Item, to_remove)
+            core::slice::from_raw_parts_mut(res.as_mut_ptr(), to_remove)
         }
     }
 
@@ -132,6 +180,17 @@ impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, Arr> {
         }
     }
 
+    /// Borrows a region of the buffer as a type implementing `Deref`, `AsRef`,
+    /// `BorrowUninitSlice` and `BorrowOutSlice`.
+    ///
+    /// The region is one of `Written`, `Uninit` or `Entire`.
+    pub fn cursed<R: Region>(&mut self) -> Cursed<'_, R, Item, Arr> {
+        Cursed {
+            _phantom: PhantomData,
+            cursor: self,
+        }
+    }
+
     /// Splits the internal buffer at current position and returns both
     /// initialized and uninitialized part.
     pub fn split_mut(&mut self) -> (&mut [Item], &mut OutSlice<Item>) {
@@ -146,12 +205,51 @@ impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, Arr> {
     pub fn try_cast_initialized(self) -> Result<<Arr as InitTc<Item>>::Output, Self> where Arr: Sized + InitTc<Item> {
         unsafe {
             if self.position == self.data.borrow_uninit_slice().len() {
-                Ok(self.data.cast())
+                // The items are now owned by the returned container.
+                let this = ManuallyDrop::new(self);
+                Ok(core::ptr::read(&this.data).cast())
             } else {
                 Err(self)
             }
         }
     }
+
+    /// Sets the position to 0, dropping the written items if the cursor owns
+    /// them.
+    fn drop_written(&mut self) {
+        let owned = self.data.is_uninit_storage();
+        let written: *mut [Item] = self.written_mut();
+        // Set the position first, so that a panicking destructor doesn't
+        // cause double drop.
+        self.position = 0;
+        if owned {
+            unsafe {
+                core::ptr::drop_in_place(written);
+            }
+        }
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursor<Item, &'a mut Arr> {
+    /// Converts the cursor into the written slice, preserving the lifetime of
+    /// the storage.
+    ///
+    /// In case of `MaybeUninit` storage, the written items are leaked.
+    pub fn into_written(self) -> &'a mut [Item] {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            // The cursor is not dropped, so the storage is not used anymore.
+            let data = core::ptr::read(&this.data);
+            let slice = &mut data.borrow_out_slice()[..this.position];
+            core::slice::from_raw_parts_mut(slice.as_mut_ptr(), slice.len())
+        }
+    }
+}
+
+impl<Item, Arr: BorrowOutSlice<Item> + ?Sized> Drop for Cursor<Item, Arr> {
+    fn drop(&mut self) {
+        self.drop_written();
+    }
 }
 
 impl<Item, Arr: BorrowOutSlice<Item>> From<Arr> for Cursor<Item, Arr> {
@@ -160,11 +258,306 @@ impl<Item, Arr: BorrowOutSlice<Item>> From<Arr> for Cursor<Item, Arr> {
     }
 }
 
+impl<Arr: BorrowOutSlice<u8> + ?Sized> Cursor<u8, Arr> {
+    /// Writes as many bytes as possible, returning their count.
+    fn write_bytes_min(&mut self, bytes: &[u8]) -> usize {
+        let written = self.split_mut().1.init_with_copy_from_slice_min(bytes).len();
+        self.position += written;
+        written
+    }
+}
+
+/// Writes the whole string or nothing.
+///
+/// Returns `fmt::Error` if the string doesn't fit into the remaining part of
+/// the buffer.
+impl<Arr: BorrowOutSlice<u8> + ?Sized> core::fmt::Write for Cursor<u8, Arr> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if s.len() > self.remaining_count() {
+            return Err(core::fmt::Error);
+        }
+        self.write_bytes_min(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use super::Cursor;
+    use super::super::BorrowOutSlice;
+    use crate::io::UninitRead;
+    use std::io;
+
+    impl<Arr: BorrowOutSlice<u8> + ?Sized> Cursor<u8, Arr> {
+        /// Reads from the reader into the remaining part of the buffer and
+        /// advances the position by the number of bytes read.
+        ///
+        /// The buffer is not zeroed, since the reader is known to not read
+        /// from it. Use `read_from_zeroing` for other readers.
+        pub fn read_from<R: UninitRead + ?Sized>(&mut self, reader: &mut R) -> io::Result<usize> {
+            let len = crate::io::read_into(reader, self.split_mut().1)?.len();
+            self.position += len;
+            Ok(len)
+        }
+
+        /// Reads from an arbitrary reader into the remaining part of the
+        /// buffer and advances the position by the number of bytes read.
+        ///
+        /// The remaining part of the buffer is zeroed first if needed.
+        pub fn read_from_zeroing<R: io::Read + ?Sized>(&mut self, reader: &mut R) -> io::Result<usize> {
+            let len = crate::io::read_into_zeroing(reader, self.split_mut().1)?.len();
+            self.position += len;
+            Ok(len)
+        }
+    }
+
+    /// Returns `WriteZero` error if the buffer is full.
+    ///
+    /// `write_all` writes either everything or nothing, so the position is
+    /// not changed in case of failure.
+    impl<Arr: BorrowOutSlice<u8> + ?Sized> io::Write for Cursor<u8, Arr> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.remaining_count() == 0 && !buf.is_empty() {
+                return Err(io::ErrorKind::WriteZero.into());
+            }
+            Ok(self.write_bytes_min(buf))
+        }
+
+        fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+            if buf.len() > self.remaining_count() {
+                return Err(io::ErrorKind::WriteZero.into());
+            }
+            self.write_bytes_min(buf);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
+
+/// Draining iterator moving the items out of `MaybeUninit` storage.
+///
+/// This is returned from `Cursor::drain_back` and `UninitArray::drain`. If
+/// the iterator is leaked, the remaining items are leaked too.
+pub struct Drain<'a, T> {
+    // These items are owned by the iterator.
+    items: core::slice::IterMut<'a, T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().map(|item| unsafe { core::ptr::read(item) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.items.next_back().map(|item| unsafe { core::ptr::read(item) })
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        let remaining: *mut [T] = core::mem::replace(&mut self.items, [].iter_mut()).into_slice();
+        unsafe {
+            core::ptr::drop_in_place(remaining);
+        }
+    }
+}
+
+mod sealed {
+    use core::ops::Range;
+
+    pub trait Region {
+        /// `true` if the region never contains items written by the cursor.
+        const UNWRITTEN_ONLY: bool;
+
+        fn range(position: usize, len: usize) -> Range<usize>;
+    }
+}
+
+/// Marker trait for regions of `Cursor` that can be borrowed using `Cursed`.
+///
+/// This trait is sealed, the only regions are `Written`, `Uninit` and
+/// `Entire`.
+pub trait Region: sealed::Region {}
+
+/// The part of the buffer that was already written.
+pub enum Written {}
+
+/// The part of the buffer that wasn't written yet.
+pub enum Uninit {}
+
+/// The whole buffer, regardless of position.
+pub enum Entire {}
+
+impl sealed::Region for Written {
+    const UNWRITTEN_ONLY: bool = false;
+
+    fn range(position: usize, _len: usize) -> Range<usize> {
+        0..position
+    }
+}
+
+impl sealed::Region for Uninit {
+    const UNWRITTEN_ONLY: bool = true;
+
+    fn range(position: usize, len: usize) -> Range<usize> {
+        position..len
+    }
+}
+
+impl sealed::Region for Entire {
+    const UNWRITTEN_ONLY: bool = false;
+
+    fn range(_position: usize, len: usize) -> Range<usize> {
+        0..len
+    }
+}
+
+impl Region for Written {}
+impl Region for Uninit {}
+impl Region for Entire {}
+
+/// Borrowed region of `Cursor`.
+///
+/// This is created by `Cursor::cursed` method and it implements `Deref`,
+/// `AsRef`, `BorrowUninitSlice` and `BorrowOutSlice` for the selected region,
+/// so it can be passed to generic code. `Cursed<'_, Written, ..>` derefs to
+/// `[Item]`, the other regions deref to `OutSlice<Item>`.
+///
+/// Writing into the region doesn't change the position of the cursor.
+pub struct Cursed<'a, R: Region, Item, Arr: BorrowOutSlice<Item> + ?Sized> {
+    _phantom: PhantomData<R>,
+    cursor: &'a mut Cursor<Item, Arr>,
+}
+
+impl<'a, R: Region, Item, Arr: BorrowOutSlice<Item> + ?Sized> Cursed<'a, R, Item, Arr> {
+    fn range(&self) -> Range<usize> {
+        R::range(self.cursor.position, self.cursor.data.borrow_uninit_slice().len())
+    }
+
+    /// Returns the underlying cursor.
+    pub fn into_cursor(self) -> &'a mut Cursor<Item, Arr> {
+        self.cursor
+    }
+}
+
+unsafe impl<'a, R: Region, Item, Arr: BorrowOutSlice<Item> + ?Sized> BorrowUninitSlice<Item> for Cursed<'a, R, Item, Arr> {
+    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
+        &self.cursor.data.borrow_uninit_slice()[self.range()]
+    }
+}
+
+unsafe impl<'a, R: Region, Item, Arr: BorrowOutSlice<Item> + ?Sized> BorrowOutSlice<Item> for Cursed<'a, R, Item, Arr> {
+    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
+        let range = self.range();
+        &mut self.cursor.data.borrow_out_slice()[range]
+    }
+
+    // Items written by the cursor are owned by the cursor, so only the
+    // unwritten part may be treated as uninit storage.
+    fn is_uninit_storage(&self) -> bool {
+        <R as sealed::Region>::UNWRITTEN_ONLY && self.cursor.data.is_uninit_storage()
+    }
+}
+
+unsafe impl<'a, Item, Arr: UninitStorage<Item> + ?Sized> UninitStorage<Item> for Cursed<'a, Uninit, Item, Arr> {}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> Deref for Cursed<'a, Written, Item, Arr> {
+    type Target = [Item];
+
+    fn deref(&self) -> &Self::Target {
+        self.cursor.written()
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> DerefMut for Cursed<'a, Written, Item, Arr> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.cursor.written_mut()
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> AsRef<[Item]> for Cursed<'a, Written, Item, Arr> {
+    fn as_ref(&self) -> &[Item] {
+        self
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> AsMut<[Item]> for Cursed<'a, Written, Item, Arr> {
+    fn as_mut(&mut self) -> &mut [Item] {
+        self
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> Deref for Cursed<'a, Uninit, Item, Arr> {
+    type Target = OutSlice<Item>;
+
+    fn deref(&self) -> &Self::Target {
+        self.borrow_uninit_slice().into()
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> DerefMut for Cursed<'a, Uninit, Item, Arr> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.borrow_out_slice()
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> AsRef<OutSlice<Item>> for Cursed<'a, Uninit, Item, Arr> {
+    fn as_ref(&self) -> &OutSlice<Item> {
+        self
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> AsMut<OutSlice<Item>> for Cursed<'a, Uninit, Item, Arr> {
+    fn as_mut(&mut self) -> &mut OutSlice<Item> {
+        self
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> Deref for Cursed<'a, Entire, Item, Arr> {
+    type Target = OutSlice<Item>;
+
+    fn deref(&self) -> &Self::Target {
+        self.borrow_uninit_slice().into()
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> DerefMut for Cursed<'a, Entire, Item, Arr> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.borrow_out_slice()
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> AsRef<OutSlice<Item>> for Cursed<'a, Entire, Item, Arr> {
+    fn as_ref(&self) -> &OutSlice<Item> {
+        self
+    }
+}
+
+impl<'a, Item, Arr: BorrowOutSlice<Item> + ?Sized> AsMut<OutSlice<Item>> for Cursed<'a, Entire, Item, Arr> {
+    fn as_mut(&mut self) -> &mut OutSlice<Item> {
+        self
+    }
+}
+
 #[cfg(feature = "alloc")]
 mod alloc_impls {
     use super::Cursor;
-    use core::mem::MaybeUninit;
+    use core::mem::{ManuallyDrop, MaybeUninit};
     use super::super::BorrowOutSlice;
+    use crate::boxed::AllocError;
 
     use alloc::boxed::Box;
     use alloc::vec::Vec;
@@ -181,7 +574,8 @@ mod alloc_impls {
 
         /// Constructs the `Cursor` from `Vec` using whole capacity of the vec.
         ///
-        /// This method currently leaks all present items
+        /// This method currently leaks all present items. Use
+        /// `from_vec_preserving_len` to keep them.
         pub fn from_vec_entire_capaity<T>(mut vec: Vec<T>) -> Self where Box<[MaybeUninit<T>]>: BorrowOutSlice<Item> {
             unsafe {
                 let ptr = vec.as_mut_ptr();
@@ -200,6 +594,30 @@ mod alloc_impls {
                 }
             }
         }
+
+        /// Allocates a cursor with given capacity.
+        ///
+        /// Returns an error instead of aborting if the allocation fails.
+        pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocError> {
+            crate::boxed::try_new_uninit_slice(capacity).map(Cursor::new)
+        }
+
+        /// Converts the cursor back into `Vec`.
+        ///
+        /// The written items become the items of the `Vec` and the capacity
+        /// of the `Vec` is the length of the underlying slice. Thus converting
+        /// a `Vec` into a `Cursor` using `from_vec_preserving_len` and back
+        /// doesn't reallocate.
+        pub fn into_vec(self) -> Vec<Item> {
+            let this = ManuallyDrop::new(self);
+            unsafe {
+                // The written items are now owned by the returned Vec.
+                let data = core::ptr::read(&this.data);
+                let capacity = data.len();
+                let ptr = Box::into_raw(data) as *mut Item;
+                Vec::from_raw_parts(ptr, this.position, capacity)
+            }
+        }
     }
 
     impl<Item> Cursor<Item, Box<[Item]>> where Box<[Item]>: BorrowOutSlice<Item> {
@@ -215,7 +633,19 @@ mod alloc_impls {
 #[cfg(test)]
 mod test {
     use super::*;
+    use core::cell::Cell;
     use core::mem::MaybeUninit;
+    use crate::test_util::DropCounter;
+
+    #[test]
+    fn written_array() {
+        let mut uninit = [MaybeUninit::uninit(); 4];
+        let mut cursor = Cursor::new(&mut uninit);
+        cursor.push(1u8).expect("Array full");
+        assert_eq!(cursor.written_array::<2>(), None);
+        cursor.push(2).expect("Array full");
+        assert_eq!(cursor.written_array::<2>(), Some(&[1, 2]));
+    }
 
     #[test]
     fn slice() {
@@ -306,4 +736,264 @@ mod test {
         let arr = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
         assert_eq!(&*arr, &[0, 24, 42, 47]);
     }
+
+    #[test]
+    fn large_arr() {
+        let uninit = [MaybeUninit::<f32>::uninit(); 4096];
+        let mut cursor = Cursor::new(uninit);
+        cursor.push_iter((0..4096).map(|i| i as f32));
+
+        let arr = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
+        assert_eq!(arr.len(), 4096);
+        assert_eq!(arr[4095], 4095.0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn large_boxed_arr() {
+        use alloc::boxed::Box;
+
+        let uninit = Box::new([MaybeUninit::<u16>::uninit(); 100]);
+        let mut cursor = Cursor::new(uninit);
+        cursor.push_iter(0..100);
+
+        let arr: Box<[u16; 100]> = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
+        assert_eq!(arr[99], 99);
+    }
+
+    #[test]
+    fn init_arr() {
+        let mut cursor = Cursor::new([0u8; 2]);
+        cursor.push(1).expect("Array full");
+        cursor.push(2).expect("Array full");
+        let arr: [u8; 2] = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
+        assert_eq!(arr, [1, 2]);
+    }
+
+    #[test]
+    fn init_arr_ref() {
+        let mut init = [0u8; 2];
+        let mut cursor = Cursor::new(&mut init);
+        cursor.push(1).expect("Array full");
+        assert!(cursor.try_cast_initialized().is_err());
+
+        let mut cursor = Cursor::new(&mut init);
+        cursor.push(1).expect("Array full");
+        cursor.push(2).expect("Array full");
+        let arr: &mut [u8; 2] = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
+        assert_eq!(arr, &[1, 2]);
+    }
+
+    #[test]
+    fn init_slice_ref() {
+        let mut init = [0u8; 2];
+        let mut cursor = Cursor::new(&mut init as &mut [u8]);
+        cursor.push(1).expect("Array full");
+        cursor.push(2).expect("Array full");
+        let slice: &mut [u8] = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
+        assert_eq!(slice, &[1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn init_boxed_arr() {
+        use alloc::boxed::Box;
+
+        let mut cursor = Cursor::new(Box::new([0u8; 2]));
+        cursor.push(1).expect("Array full");
+        cursor.push(2).expect("Array full");
+        let arr: Box<[u8; 2]> = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
+        assert_eq!(*arr, [1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn init_boxed_slice() {
+        use alloc::boxed::Box;
+        use alloc::vec;
+
+        let mut cursor = Cursor::from_vec_resizing(vec![0u8; 2]);
+        cursor.push(1).expect("Array full");
+        cursor.push(2).expect("Array full");
+        let slice: Box<[u8]> = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
+        assert_eq!(&*slice, &[1, 2]);
+    }
+
+    #[test]
+    fn pop_owned() {
+        let counter = Cell::new(0);
+        let mut uninit: [MaybeUninit<DropCounter>; 4] = [(); 4].map(|_| MaybeUninit::uninit());
+        let mut cursor = Cursor::new(&mut uninit);
+        for _ in 0..4 {
+            assert!(cursor.push(DropCounter(&counter)).is_ok());
+        }
+        drop(cursor.pop_owned());
+        assert_eq!(counter.get(), 1);
+        let mut drain = cursor.drain_back(2);
+        assert_eq!(drain.len(), 2);
+        drop(drain.next());
+        assert_eq!(counter.get(), 2);
+        drop(drain);
+        assert_eq!(counter.get(), 3);
+        assert_eq!(cursor.written().len(), 1);
+        assert_eq!(cursor.drain_back(5).count(), 1);
+        assert_eq!(counter.get(), 4);
+        assert!(cursor.pop_owned().is_none());
+    }
+
+    #[test]
+    fn drops_written_uninit() {
+        let counter = Cell::new(0);
+        let mut uninit: [MaybeUninit<DropCounter>; 4] = [(); 4].map(|_| MaybeUninit::uninit());
+        let mut cursor = Cursor::new(&mut uninit);
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        cursor.reset();
+        assert_eq!(counter.get(), 2);
+
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        drop(cursor);
+        assert_eq!(counter.get(), 5);
+    }
+
+    #[test]
+    fn keeps_written_init() {
+        let counter = Cell::new(0);
+        let mut init = [DropCounter(&counter), DropCounter(&counter)];
+        let mut cursor = Cursor::new(&mut init as &mut [DropCounter]);
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        drop(cursor);
+        assert_eq!(counter.get(), 0);
+        drop(init);
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    fn cast_doesnt_drop() {
+        let counter = Cell::new(0);
+        let mut uninit: [MaybeUninit<DropCounter>; 2] = [(); 2].map(|_| MaybeUninit::uninit());
+        let mut cursor = Cursor::new(&mut uninit);
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        let arr = cursor.try_cast_initialized().unwrap_or_else(|_| panic!("Cursor not filled"));
+        assert_eq!(counter.get(), 0);
+        unsafe {
+            core::ptr::drop_in_place(arr);
+        }
+        assert_eq!(counter.get(), 2);
+    }
+
+    fn fill_zeroes<S: BorrowOutSlice<u8>>(mut slice: S) -> usize {
+        slice.zero_if_needed().len()
+    }
+
+    fn sum<S: BorrowUninitSlice<u8>>(slice: S) -> u32 {
+        unsafe {
+            slice.assume_init().iter().map(|x| u32::from(*x)).sum()
+        }
+    }
+
+    #[test]
+    fn cursed() {
+        let mut uninit = [MaybeUninit::uninit(); 4];
+        let mut cursor = Cursor::new(&mut uninit);
+        cursor.push(1).expect("Array full");
+        cursor.push(2).expect("Array full");
+
+        assert_eq!(&*cursor.cursed::<Written>(), &[1, 2]);
+        assert_eq!(sum(cursor.cursed::<Written>()), 3);
+        assert_eq!(cursor.cursed::<Uninit>().len(), 2);
+        assert_eq!(cursor.cursed::<Entire>().len(), 4);
+        cursor.cursed::<Written>()[1] = 5;
+        assert_eq!(fill_zeroes(cursor.cursed::<Uninit>()), 2);
+        assert_eq!(cursor.written(), &[1, 5]);
+        assert_eq!(cursor.remaining_count(), 2);
+        assert_eq!(fill_zeroes(cursor.cursed::<Entire>()), 4);
+        assert_eq!(cursor.written(), &[0, 0]);
+    }
+
+    #[test]
+    fn fmt_write() {
+        use core::fmt::Write;
+
+        let mut uninit = [MaybeUninit::uninit(); 8];
+        let mut cursor = Cursor::new(&mut uninit);
+        let suffix = "ab";
+        write!(cursor, "{}-{}", 42, suffix).expect("Array full");
+        assert_eq!(cursor.written(), b"42-ab");
+        assert!(write!(cursor, "{}", 1234).is_err());
+        cursor.write_str("xyz").expect("Array full");
+        assert_eq!(cursor.written(), b"42-abxyz");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_write() {
+        use std::io::{self, Write};
+
+        let mut uninit = [MaybeUninit::uninit(); 8];
+        let mut cursor = Cursor::new(&mut uninit);
+        cursor.write_all(b"hello").expect("Array full");
+        let err = cursor.write_all(b"world").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert_eq!(cursor.written(), b"hello");
+        assert_eq!(cursor.write(b"world").expect("Array full"), 3);
+        assert_eq!(cursor.written(), b"hellowor");
+        assert_eq!(cursor.write(b"ld").unwrap_err().kind(), io::ErrorKind::WriteZero);
+        assert_eq!(cursor.write(b"").expect("empty write failed"), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn read_from() {
+        let mut uninit = [MaybeUninit::uninit(); 8];
+        let mut cursor = Cursor::new(&mut uninit);
+        let mut reader = &b"hello world"[..];
+        assert_eq!(cursor.read_from(&mut reader).expect("read failed"), 8);
+        assert_eq!(cursor.written(), b"hello wo");
+        cursor.reset();
+        assert_eq!(cursor.read_from_zeroing(&mut reader).expect("read failed"), 3);
+        assert_eq!(cursor.written(), b"rld");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn try_with_capacity() {
+        let mut cursor = Cursor::try_with_capacity(2).expect("allocation failed");
+        cursor.push(1u8).expect("Array full");
+        cursor.push(2).expect("Array full");
+        assert_eq!(cursor.written(), &[1, 2]);
+        assert!(Cursor::<u8, _>::try_with_capacity(isize::MAX as usize + 1).is_err());
+        assert!(Cursor::<u64, _>::try_with_capacity(usize::MAX / 8 + 1).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn into_vec() {
+        let mut vec = alloc::vec::Vec::with_capacity(8);
+        vec.push(1u8);
+        let capacity = vec.capacity();
+        let mut cursor = Cursor::from_vec_preserving_len(vec);
+        cursor.push(2).expect("Array full");
+        let vec = cursor.into_vec();
+        assert_eq!(vec, [1, 2]);
+        assert_eq!(vec.capacity(), capacity);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn boxed_slice_drops_written() {
+        use alloc::vec::Vec;
+
+        let counter = Cell::new(0);
+        let mut vec = Vec::with_capacity(4);
+        vec.push(DropCounter(&counter));
+        vec.push(DropCounter(&counter));
+        let mut cursor = Cursor::from_vec_preserving_len(vec);
+        assert!(cursor.push(DropCounter(&counter)).is_ok());
+        drop(cursor);
+        assert_eq!(counter.get(), 3);
+    }
 }
@@ -2,15 +2,17 @@
 //! uninitialized memory
 
 mod cursor;
+mod str_cursor;
 
-pub use self::cursor::Cursor;
+pub use self::cursor::{Cursor, Cursed, Region, Written, Uninit, Entire, Drain};
+pub use self::str_cursor::StrCursor;
 
 use core::mem::MaybeUninit;
 use core::ptr::NonNull;
 use core::ops::{Deref, DerefMut, Index, IndexMut};
 use core::slice::SliceIndex;
 use crate::deref_markers::SameDataDeref;
-use crate::zeroed::ZeroValid;
+use crate::zeroed::{AnyValid, ZeroValid};
 
 /// Abstraction allowing treating `T` and `&T where T: Copy` equally. 
 ///
@@ -129,6 +131,30 @@ impl<T> OutSlice<T> {
         }
     }
 
+    /// Splits the first `N` items off the slice as an array.
+    ///
+    /// Returns `None` if the slice is shorter than `N`. The array is returned
+    /// as `Out` rather than `&mut [MaybeUninit<T>; N]` for the same reasons
+    /// `OutSlice` exists.
+    pub fn split_array_out<const N: usize>(&mut self) -> Option<(super::Out<'_, [T; N]>, &mut OutSlice<T>)> {
+        if self.len() < N {
+            return None;
+        }
+        let (first, rest) = self.split_at_mut(N);
+        // The length of `first` is exactly `N`.
+        let array = unsafe { &mut *(first.as_mut_ptr() as *mut MaybeUninit<[T; N]>) };
+        Some((array.into(), rest))
+    }
+
+    /// Accesses the slice as an array if its length is exactly `N`.
+    pub fn as_array_out<const N: usize>(&mut self) -> Option<super::Out<'_, [T; N]>> {
+        if self.len() != N {
+            return None;
+        }
+        let array = unsafe { &mut *(self.as_mut_ptr() as *mut MaybeUninit<[T; N]>) };
+        Some(array.into())
+    }
+
     /// Transforms the slice into initialized version.
     ///
     /// # Safety
@@ -190,35 +216,203 @@ impl<T> OutSlice<T> {
         }
     }
 
+    /// Overwrites the whole slice with zeroes in a way that can't be
+    /// optimized out.
+    ///
+    /// This is useful for wiping secrets. See `ZeroValid::secure_zero`.
+    pub fn secure_zero(&mut self) -> &mut [T] where T: ZeroValid {
+        unsafe {
+            self.as_raw_mut().secure_zero();
+            self.assume_init_mut()
+        }
+    }
+
     /// Uses the iterator to initialize the slice
+    ///
+    /// The slice doesn't know whether it was borrowed from `MaybeUninit`
+    /// storage, so the already written items are not dropped if the iterator
+    /// panics. Use `BorrowOutSlice::init_from_iter` on the storage to drop
+    /// them.
     pub fn init_from_iter<I>(&mut self, iter: I) -> &mut [T] where I: IntoIterator, I::Item: TakeItem<T> {
+        self.init_from_iter_impl(iter, false)
+    }
+
+    /// Uses the iterator of `Result`s to initialize the slice, stopping at
+    /// the first error.
+    ///
+    /// In case of error, the number of written items is returned along with
+    /// the error. The written items are not dropped, see `init_from_iter`.
+    pub fn try_init_from_iter<I, U, E>(&mut self, iter: I) -> Result<&mut [T], (usize, E)> where I: IntoIterator<Item=Result<U, E>>, U: TakeItem<T> {
+        self.try_init_from_iter_impl(iter, false)
+    }
+
+    /// Initializes the slice by cloning the value into every item.
+    pub fn fill(&mut self, value: T) -> &mut [T] where T: Clone {
+        self.fill_impl(value, false)
+    }
+
+    /// Initializes the slice with values returned from the closure.
+    pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) -> &mut [T] {
+        self.init_from_iter(core::iter::repeat_with(f))
+    }
+
+    /// Initializes the slice with values returned from the closure called
+    /// with the index of each item.
+    pub fn init_with<F: FnMut(usize) -> T>(&mut self, f: F) -> &mut [T] {
+        let len = self.len();
+        self.init_from_iter((0..len).map(f))
+    }
+
+    /// Initializes the slice with values returned from the closure called
+    /// with the index of each item, stopping at the first error.
+    ///
+    /// The already written items are not dropped in case of error, see
+    /// `init_from_iter`.
+    pub fn try_init_with<E, F: FnMut(usize) -> Result<T, E>>(&mut self, f: F) -> Result<&mut [T], E> {
+        self.try_init_with_impl(f, false)
+    }
+
+    // `repeat_n` needs a newer compiler.
+    #[allow(clippy::manual_repeat_n)]
+    fn fill_impl(&mut self, value: T, owned: bool) -> &mut [T] where T: Clone {
+        let len = self.len();
+        self.init_from_iter_impl(core::iter::repeat(value).take(len), owned)
+    }
+
+    fn try_init_with_impl<E, F: FnMut(usize) -> Result<T, E>>(&mut self, f: F, owned: bool) -> Result<&mut [T], E> {
+        let len = self.len();
+        self.try_init_from_iter_impl((0..len).map(f), owned).map_err(|(_, error)| error)
+    }
+
+    fn init_from_iter_impl<I>(&mut self, iter: I, owned: bool) -> &mut [T] where I: IntoIterator, I::Item: TakeItem<T> {
+        let iter = iter.into_iter().map(Ok::<_, core::convert::Infallible>);
+        match self.try_init_from_iter_impl(iter, owned) {
+            Ok(slice) => slice,
+            Err((_, never)) => match never {},
+        }
+    }
+
+    /// Initializes the slice from the iterator, dropping the written items
+    /// in case of error or panic if `owned` is `true`.
+    fn try_init_from_iter_impl<I, U, E>(&mut self, iter: I, owned: bool) -> Result<&mut [T], (usize, E)> where I: IntoIterator<Item=Result<U, E>>, U: TakeItem<T> {
+        let len = self.len();
+        let mut guard = DropGuard {
+            ptr: self.as_mut_ptr(),
+            initialized: 0,
+            owned,
+        };
+
+        for item in iter.into_iter().take(len) {
+            let item = match item {
+                Ok(item) => item.take_item(),
+                Err(error) => return Err((guard.initialized, error)),
+            };
+            unsafe {
+                guard.ptr.add(guard.initialized).write(item);
+            }
+            guard.initialized += 1;
+        }
+
+        let guard = core::mem::ManuallyDrop::new(guard);
         unsafe {
-            // Yes, we really want map and then count.
-            // If you know some nice, functional way of implementing this
-            // without clippy screaming, make a PR.
-            #[allow(clippy::suspicious_map)]
-            let len = self.iter_mut().zip(iter).map(|(dest, source)| dest.write(source.take_item())).count();
-            self[..len].assume_init_mut()
+            Ok(core::slice::from_raw_parts_mut(guard.ptr, guard.initialized))
         }
     }
 
     /// Initializes the slice by copying another slice into it.
     ///
-    /// This is a specialized version of `init_from_iter`, which may be more
-    /// performant.
+    /// This is a specialized version of `init_from_iter` and
+    /// `clone_from_slice` performing a single `memcpy`.
     ///
     /// # Panics
     ///
     /// The method panics if the slices are of different lengths. Use indexing
     /// opertors to make the lengths same.
     pub fn copy_from_slice(&mut self, slice: &[T]) -> &mut [T] where T: Copy {
+        if self.len() != slice.len() {
+            panic!("source slice length ({}) does not match destination slice length ({})", slice.len(), self.len());
+        }
+        unsafe {
+            // `&mut self` can't overlap with `&[T]`.
+            core::ptr::copy_nonoverlapping(slice.as_ptr(), self.as_mut_ptr(), slice.len());
+            self.assume_init_mut()
+        }
+    }
+
+    /// Initializes the slice by cloning items of another slice into it.
+    ///
+    /// If `T` is `Copy`, use `copy_from_slice` instead, which is a single
+    /// `memcpy`. The already cloned items are not dropped if `clone` panics,
+    /// see `init_from_iter`.
+    ///
+    /// # Panics
+    ///
+    /// The method panics if the slices are of different lengths. Use indexing
+    /// opertors to make the lengths same.
+    pub fn clone_from_slice(&mut self, slice: &[T]) -> &mut [T] where T: Clone {
+        self.clone_from_slice_impl(slice, false)
+    }
+
+    fn clone_from_slice_impl(&mut self, slice: &[T], owned: bool) -> &mut [T] where T: Clone {
+        if self.len() != slice.len() {
+            panic!("source slice length ({}) does not match destination slice length ({})", slice.len(), self.len());
+        }
+        let mut guard = DropGuard {
+            ptr: self.as_mut_ptr(),
+            initialized: 0,
+            owned,
+        };
+        for item in slice {
+            unsafe {
+                guard.ptr.add(guard.initialized).write(item.clone());
+            }
+            guard.initialized += 1;
+        }
+        core::mem::forget(guard);
         unsafe {
-            self.as_raw_mut().copy_from_slice(slice.borrow_uninit_slice());
             self.assume_init_mut()
         }
     }
 }
 
+/// Drops the initialized prefix of a slice unless forgotten.
+///
+/// This is used to avoid leaks if initialization fails or panics.
+struct DropGuard<T> {
+    ptr: *mut T,
+    initialized: usize,
+    owned: bool,
+}
+
+impl<T> Drop for DropGuard<T> {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe {
+                core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(self.ptr, self.initialized));
+            }
+        }
+    }
+}
+
+impl OutSlice<u8> {
+    /// Reinterprets the byte slice as a slice of `T`.
+    ///
+    /// Returns `None` if the slice is not aligned for `T`, its length is not
+    /// a multiple of the size of `T` or `T` is zero-sized.
+    pub fn cast<T: AnyValid>(&mut self) -> Option<&mut OutSlice<T>> {
+        let size = core::mem::size_of::<T>();
+        let ptr = self.as_mut_ptr();
+        if !crate::zeroed::is_castable::<T>(ptr, self.len()) {
+            return None;
+        }
+
+        unsafe {
+            let slice = core::slice::from_raw_parts_mut(ptr as *mut MaybeUninit<T>, self.len() / size);
+            Some(slice.into())
+        }
+    }
+}
+
 impl<T, R: SliceIndex<[MaybeUninit<T>], Output=[MaybeUninit<T>]>> Index<R> for OutSlice<T> {
     type Output = Self;
 
@@ -241,7 +435,7 @@ impl<'a, T> From<&'a [T]> for &'a OutSlice<T> {
             let ptr = value.as_ptr() as *const MaybeUninit<T>;
             let len = value.len();
 
-            &*(core::slice::from_raw_parts(ptr, len) as *const [MaybeUninit<T>] as *const OutSlice<T>)
+            &*(core::ptr::slice_from_raw_parts(ptr, len) as *const OutSlice<T>)
         }
     }
 }
@@ -252,7 +446,7 @@ impl<'a, T> From<&'a mut [T]> for &'a mut OutSlice<T> {
             let ptr = value.as_mut_ptr() as *mut MaybeUninit<T>;
             let len = value.len();
 
-            &mut *(core::slice::from_raw_parts_mut(ptr, len) as *mut [MaybeUninit<T>] as *mut OutSlice<T>)
+            &mut *(core::ptr::slice_from_raw_parts_mut(ptr, len) as *mut OutSlice<T>)
         }
     }
 }
@@ -279,7 +473,7 @@ unsafe impl<T> BorrowUninitSlice<T> for OutSlice<T> {
     }
 }
 
-unsafe impl<'a, T> BorrowOutSlice<T> for OutSlice<T> {
+unsafe impl<T> BorrowOutSlice<T> for OutSlice<T> {
     fn borrow_out_slice(&mut self) -> &mut OutSlice<T> {
         self
     }
@@ -331,12 +525,18 @@ impl<'a, T> Iterator for IterMut<'a, T> {
 /// * `Wrapper<ReferenceType<MaybeUninit<Item>>>`
 /// * `Wrapper<ReferenceTypeMut<Item>>`
 /// * `Wrapper<ReferenceTypeMut<MaybeUninit<Item>>>`
+///
+/// # Safety
+///
+/// `borrow_uninit_slice` must always return the same slice.
 pub unsafe trait BorrowUninitSlice<Item> {
     /// Borrows the value as a slice of `MaybeUninit<Item>`
     fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>];
 
     /// Treat the slice as initialized.
     ///
+    /// # Safety
+    ///
     /// This method is `unsafe` because calling it without **all** the slice
     /// being initialized is undefined behavior.
     unsafe fn assume_init(&self) -> &[Item] {
@@ -355,10 +555,37 @@ pub unsafe trait BorrowUninitSlice<Item> {
 /// * `Wrapper<MaybeUninit<Item>>`
 /// * `Wrapper<ReferenceTypeMut<Item>>`
 /// * `Wrapper<ReferenceTypeMut<MaybeUninit<Item>>>`
+///
+/// The two kinds of storage differ in ownership of the items: `[Item]`
+/// storage owns (and drops) them, while `[MaybeUninit<Item>]` storage never
+/// does. `is_uninit_storage` tells them apart, so that wrappers like `Cursor`
+/// can drop the items they wrote if (and only if) nobody else will.
+///
+/// # Safety
+///
+/// `borrow_out_slice` must always return the same slice as
+/// `borrow_uninit_slice` does. `is_uninit_storage` may only return `true` if
+/// the storage never drops the items on its own. It must return `true` for
+/// types implementing `UninitStorage`, since `Cursor` relies on both of them
+/// agreeing on who owns the written items.
 pub unsafe trait BorrowOutSlice<Item>: BorrowUninitSlice<Item> {
     /// Borrows the value as a mutable slice of `MaybeUninit<Item>`
     fn borrow_out_slice(&mut self) -> &mut OutSlice<Item>;
 
+    /// Returns `true` if the underlying storage is a `MaybeUninit<Item>`
+    /// buffer.
+    ///
+    /// Such storage never drops the items, so whoever initialized them is
+    /// responsible for dropping them. Items in `[Item]` storage are owned by
+    /// the storage itself, so they must not be dropped by anyone else.
+    ///
+    /// The default implementation returns `false`, which may cause leaks, but
+    /// is never unsound. Types implementing `UninitStorage` must override it
+    /// to return `true`.
+    fn is_uninit_storage(&self) -> bool {
+        false
+    }
+
     /// Zeroes the buffer if it's needed and returns it as initialized.
     ///
     /// This can be used when interfacing with an old code which doesn't
@@ -377,6 +604,54 @@ pub unsafe trait BorrowOutSlice<Item>: BorrowUninitSlice<Item> {
         }
     }
 
+    /// Uses the iterator to initialize the slice.
+    ///
+    /// Returns the initialized part of the slice. If the iterator panics, the
+    /// already written items are dropped in case of `MaybeUninit` storage.
+    fn init_from_iter<I>(&mut self, iter: I) -> &mut [Item] where I: IntoIterator, I::Item: TakeItem<Item> {
+        let owned = self.is_uninit_storage();
+        self.borrow_out_slice().init_from_iter_impl(iter, owned)
+    }
+
+    /// Uses the iterator of `Result`s to initialize the slice, stopping at
+    /// the first error.
+    ///
+    /// In case of error, the number of written items is returned along with
+    /// the error. The written items are dropped in case of `MaybeUninit`
+    /// storage, the same applies to panics.
+    fn try_init_from_iter<I, U, E>(&mut self, iter: I) -> Result<&mut [Item], (usize, E)> where I: IntoIterator<Item=Result<U, E>>, U: TakeItem<Item> {
+        let owned = self.is_uninit_storage();
+        self.borrow_out_slice().try_init_from_iter_impl(iter, owned)
+    }
+
+    /// Initializes the slice by cloning the value into every item.
+    fn fill(&mut self, value: Item) -> &mut [Item] where Item: Clone {
+        let owned = self.is_uninit_storage();
+        self.borrow_out_slice().fill_impl(value, owned)
+    }
+
+    /// Initializes the slice with values returned from the closure.
+    fn fill_with<F: FnMut() -> Item>(&mut self, f: F) -> &mut [Item] {
+        self.init_from_iter(core::iter::repeat_with(f))
+    }
+
+    /// Initializes the slice with values returned from the closure called
+    /// with the index of each item.
+    fn init_with<F: FnMut(usize) -> Item>(&mut self, f: F) -> &mut [Item] {
+        let len = self.borrow_uninit_slice().len();
+        self.init_from_iter((0..len).map(f))
+    }
+
+    /// Initializes the slice with values returned from the closure called
+    /// with the index of each item, stopping at the first error.
+    ///
+    /// The already written items are dropped in case of error if the storage
+    /// is `MaybeUninit`.
+    fn try_init_with<E, F: FnMut(usize) -> Result<Item, E>>(&mut self, f: F) -> Result<&mut [Item], E> {
+        let owned = self.is_uninit_storage();
+        self.borrow_out_slice().try_init_with_impl(f, owned)
+    }
+
     /// Initializes the slice by copying from another slice.
     ///
     /// The returned slice will have the same length as self.borrow_uninit_slice()
@@ -397,16 +672,66 @@ pub unsafe trait BorrowOutSlice<Item>: BorrowUninitSlice<Item> {
         target.init_with_copy_from_slice(&slice[..to_copy])
     }
 
+    /// Initializes the slice by cloning items of another slice.
+    ///
+    /// If `Item` is `Copy`, `init_with_copy_from_slice` should be preferred as
+    /// it's a simple `memcpy`. If `clone` panics, the already cloned items are
+    /// dropped in case of `MaybeUninit` storage.
+    ///
+    /// Panics if the lengths differ.
+    fn init_with_clone_from_slice(&mut self, slice: &[Item]) -> &mut [Item] where Item: Clone {
+        let owned = self.is_uninit_storage();
+        self.borrow_out_slice().clone_from_slice_impl(slice, owned)
+    }
+
+    /// Initializes a subslice by cloning items of another slice.
+    ///
+    /// This is similar to `init_with_clone_from_slice` except that instead of
+    /// panicking, it clones minimum of the slice lengths.
+    fn init_with_clone_from_slice_min(&mut self, slice: &[Item]) -> &mut [Item] where Item: Clone {
+        let owned = self.is_uninit_storage();
+        let to_clone = self.borrow_out_slice().len().min(slice.len());
+        let target = &mut self.borrow_out_slice()[..to_clone];
+
+        target.clone_from_slice_impl(&slice[..to_clone], owned)
+    }
+
     /// Treat the slice as initialized.
     ///
+    /// # Safety
+    ///
     /// This method is `unsafe` because calling it without **all** the slice
     /// being initialized is undefined behavior.
     unsafe fn assume_init_mut(&mut self) -> &mut [Item] {
         let slice = self.borrow_out_slice();
-        core::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut Item, slice.len())
+        core::slice::from_raw_parts_mut(slice.as_mut_ptr(), slice.len())
     }
 }
 
+/// Marker trait for storages that are statically known to contain
+/// `MaybeUninit<Item>`.
+///
+/// Such storages never drop the items, so it's safe to move the items out of
+/// them. This allows e.g. `Cursor::pop_owned` to work for non-`Copy` items.
+///
+/// This is the static counterpart of `BorrowOutSlice::is_uninit_storage`:
+/// the marker enables moving items out of the storage at compile time, while
+/// the flag tells `Cursor` at runtime whether it has to drop the written
+/// items. The two must agree, otherwise the items moved out by
+/// `Cursor::pop_owned` could be dropped again or leaked.
+///
+/// # Safety
+///
+/// The storage must never drop the items and `is_uninit_storage` must return
+/// `true`.
+pub unsafe trait UninitStorage<Item>: BorrowOutSlice<Item> {}
+
+unsafe impl<Item> UninitStorage<Item> for [MaybeUninit<Item>] {}
+unsafe impl<Item, const N: usize> UninitStorage<Item> for [MaybeUninit<Item>; N] {}
+
+unsafe impl<T, Item> UninitStorage<Item> for T where T: SameDataDeref + Deref + DerefMut + ?Sized,
+                                                 T::Target: UninitStorage<Item> {}
+
 unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>] {
     fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
         self
@@ -417,6 +742,10 @@ unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>] {
     fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
         self.into()
     }
+
+    fn is_uninit_storage(&self) -> bool {
+        true
+    }
 }
 
 unsafe impl<Item> BorrowUninitSlice<Item> for [Item] {
@@ -461,83 +790,29 @@ unsafe impl<T, Item> BorrowOutSlice<Item> for T where T: SameDataDeref + Deref +
     fn zero_if_needed(&mut self) -> &mut [Item] where Item: ZeroValid {
         (**self).zero_if_needed()
     }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 0] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 0] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 0] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 0] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
 
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
+    fn is_uninit_storage(&self) -> bool {
+        (**self).is_uninit_storage()
     }
 }
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 1] {
+unsafe impl<Item, const N: usize> BorrowUninitSlice<Item> for [MaybeUninit<Item>; N] {
     fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
         self
     }
 }
 
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 1] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 1] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 1] {
+unsafe impl<Item, const N: usize> BorrowOutSlice<Item> for [MaybeUninit<Item>; N] {
     fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
         (self as &mut [_]).into()
     }
 
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 2] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 2] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
+    fn is_uninit_storage(&self) -> bool {
+        true
     }
 }
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 2] {
+unsafe impl<Item, const N: usize> BorrowUninitSlice<Item> for [Item; N] {
     fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
         unsafe {
             let slice = self as &[_];
@@ -546,7 +821,7 @@ unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 2] {
     }
 }
 
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 2] {
+unsafe impl<Item, const N: usize> BorrowOutSlice<Item> for [Item; N] {
     fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
         (self as &mut [_]).into()
     }
@@ -556,932 +831,196 @@ unsafe impl<Item> BorrowOutSlice<Item> for [Item; 2] {
     }
 }
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 3] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 3] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 3] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 3] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::cell::Cell;
+    use crate::test_util::DropCounter;
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 4] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
 
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 4] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
+    fn uninit_arr<T, const N: usize>() -> [MaybeUninit<T>; N] {
+        [(); N].map(|_| MaybeUninit::uninit())
     }
-}
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 4] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
+    #[test]
+    fn try_init_from_iter() {
+        let mut uninit = uninit_arr::<u8, 4>();
+        let iter = [Ok(1), Ok(2), Err("fail"), Ok(4)];
+        assert_eq!(BorrowOutSlice::<u8>::try_init_from_iter(&mut uninit, iter).unwrap_err(), (2, "fail"));
 
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 4] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
+        let iter = [Ok(1), Ok(2), Ok(3), Ok(4), Err("fail")];
+        assert_eq!(BorrowOutSlice::<u8>::try_init_from_iter(&mut uninit, iter).unwrap(), &[1, 2, 3, 4]);
     }
 
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
+    #[test]
+    fn try_init_from_iter_drops() {
+        let counter = Cell::new(0);
+        let mut uninit = uninit_arr::<DropCounter, 4>();
+        let iter = (0..4).map(|i| if i < 3 { Ok(DropCounter(&counter)) } else { Err(i) });
+        assert_eq!(BorrowOutSlice::<DropCounter>::try_init_from_iter(&mut uninit, iter).err().map(|(count, _)| count), Some(3));
+        assert_eq!(counter.get(), 3);
     }
-}
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 5] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
+    #[test]
+    fn fill() {
+        let mut uninit = uninit_arr::<u8, 3>();
+        assert_eq!(uninit.borrow_out_slice().fill(7), &[7, 7, 7]);
+        let mut next = 0;
+        assert_eq!(uninit.borrow_out_slice().fill_with(|| { next += 1; next }), &[1, 2, 3]);
+        assert_eq!(BorrowOutSlice::<u8>::init_with(&mut uninit, |i| i as u8 * 2), &[0, 2, 4]);
     }
-}
 
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 5] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
+    #[test]
+    fn try_init_with() {
+        let counter = Cell::new(0);
+        let mut uninit = uninit_arr::<DropCounter, 4>();
+        let result = BorrowOutSlice::<DropCounter>::try_init_with(&mut uninit, |i| if i < 2 { Ok(DropCounter(&counter)) } else { Err(i) });
+        assert_eq!(result.err(), Some(2));
+        assert_eq!(counter.get(), 2);
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 5] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
+        let result = BorrowOutSlice::<DropCounter>::try_init_with(&mut uninit, |_| Ok::<_, ()>(DropCounter(&counter)));
+        assert_eq!(result.map(|slice| slice.len()), Ok(4));
+        assert_eq!(counter.get(), 2);
     }
-}
 
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 5] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
+    #[test]
+    fn split_array_out() {
+        let mut uninit = uninit_arr::<u8, 6>();
+        let out = uninit.borrow_out_slice();
+        assert!(out.split_array_out::<7>().is_none());
+        let (header, rest) = out.split_array_out::<2>().expect("slice too short");
+        assert_eq!(header.write([1, 2]), &[1, 2]);
+        assert_eq!(rest.len(), 4);
+        assert!(rest.as_array_out::<3>().is_none());
+        let body = rest.as_array_out::<4>().expect("wrong length").into_out_slice();
+        assert_eq!(body.init_with(|i| i as u8), &[0, 1, 2, 3]);
+        assert_eq!(unsafe { out.assume_init() }, &[1, 2, 0, 1, 2, 3]);
     }
 
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
+    #[test]
+    fn split_array_out_empty() {
+        let mut uninit = uninit_arr::<u8, 2>();
+        let out: &mut OutSlice<u8> = uninit.borrow_out_slice();
+        let (empty, rest) = out.split_array_out::<0>().expect("slice too short");
+        assert_eq!(empty.write([]), &[]);
+        assert_eq!(rest.len(), 2);
+        assert!(out.as_array_out::<0>().is_none());
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 6] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
+        let empty = &mut out[2..];
+        let (array, rest) = empty.split_array_out::<0>().expect("slice too short");
+        assert_eq!(array.into_out_slice().len(), 0);
+        assert_eq!(rest.len(), 0);
+        assert!(empty.split_array_out::<1>().is_none());
+        assert_eq!(empty.as_array_out::<0>().expect("wrong length").write([]), &[]);
     }
-}
 
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 6] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
+    #[test]
+    fn copy_from_slice() {
+        let mut uninit = uninit_arr::<u32, 4>();
+        let out: &mut OutSlice<u32> = uninit.borrow_out_slice();
+        assert_eq!(out[1..].copy_from_slice(&[1, 2, 3]), &[1, 2, 3]);
+        assert_eq!(BorrowOutSlice::<u32>::init_with_copy_from_slice_min(&mut uninit, &[4, 5, 6, 7, 8]), &[4, 5, 6, 7]);
     }
-}
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 6] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
+    #[test]
+    #[should_panic]
+    fn copy_from_slice_len_mismatch() {
+        let mut uninit = uninit_arr::<u32, 4>();
+        let out: &mut OutSlice<u32> = uninit.borrow_out_slice();
+        out.copy_from_slice(&[1, 2, 3]);
+    }
+
+    #[test]
+    fn clone_from_slice() {
+        let counter = Cell::new(0);
+        let source = [CloneCounter(&counter), CloneCounter(&counter), CloneCounter(&counter)];
+        let mut uninit = uninit_arr::<CloneCounter, 2>();
+        assert_eq!(BorrowOutSlice::<CloneCounter>::init_with_clone_from_slice_min(&mut uninit, &source).len(), 2);
+        assert_eq!(counter.get(), 2);
+        let mut uninit = uninit_arr::<CloneCounter, 3>();
+        assert_eq!(uninit.borrow_out_slice().clone_from_slice(&source).len(), 3);
+        assert_eq!(counter.get(), 5);
+    }
+
+    struct CloneCounter<'a>(&'a Cell<usize>);
+
+    impl Clone for CloneCounter<'_> {
+        fn clone(&self) -> Self {
+            self.0.set(self.0.get() + 1);
+            CloneCounter(self.0)
         }
     }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 6] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
 
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
+    #[test]
+    #[cfg(feature = "std")]
+    fn clone_from_slice_panic_drops_uninit() {
+        struct PanickingClone<'a>(&'a Cell<usize>, bool);
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 7] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
+        impl Clone for PanickingClone<'_> {
+            fn clone(&self) -> Self {
+                if self.1 {
+                    panic!("clone failed");
+                }
+                PanickingClone(self.0, false)
+            }
+        }
 
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 7] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
+        impl Drop for PanickingClone<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
 
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 7] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 7] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 8] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 8] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 8] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 8] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 9] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 9] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 9] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 9] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 10] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 10] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 10] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 10] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 11] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 11] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 11] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 11] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 12] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 12] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 12] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 12] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 13] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 13] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 13] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 13] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 14] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 14] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 14] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 14] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 15] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 15] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 15] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 15] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 16] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 16] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 16] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 16] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 17] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 17] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 17] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 17] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 18] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 18] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 18] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 18] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 19] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 19] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 19] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 19] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 20] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 20] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 20] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 20] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 21] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 21] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 21] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 21] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 22] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 22] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 22] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 22] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 23] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 23] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 23] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 23] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 24] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 24] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 24] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 24] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 25] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 25] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 25] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 25] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 26] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 26] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 26] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 26] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 27] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 27] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 27] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 27] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 28] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 28] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 28] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 28] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 29] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 29] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 29] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 29] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 30] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 30] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 30] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 30] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 31] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 31] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 31] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 31] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [MaybeUninit<Item>; 32] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        self
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [MaybeUninit<Item>; 32] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-}
-
-unsafe impl<Item> BorrowUninitSlice<Item> for [Item; 32] {
-    fn borrow_uninit_slice(&self) -> &[MaybeUninit<Item>] {
-        unsafe {
-            let slice = self as &[_];
-            core::slice::from_raw_parts(slice.as_ptr() as *const MaybeUninit<Item>, slice.len())
-        }
-    }
-}
-
-unsafe impl<Item> BorrowOutSlice<Item> for [Item; 32] {
-    fn borrow_out_slice(&mut self) -> &mut OutSlice<Item> {
-        (self as &mut [_]).into()
-    }
-
-    fn zero_if_needed(&mut self) -> &mut [Item] {
-        self
+        let counter = Cell::new(0);
+        let source = [PanickingClone(&counter, false), PanickingClone(&counter, false), PanickingClone(&counter, true)];
+        let mut uninit = uninit_arr::<PanickingClone, 3>();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            BorrowOutSlice::<PanickingClone>::init_with_clone_from_slice(&mut uninit, &source);
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 2);
+
+        let mut uninit = uninit_arr::<PanickingClone, 4>();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            BorrowOutSlice::<PanickingClone>::init_with_clone_from_slice_min(&mut uninit, &source);
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 4);
+
+        // Items in initialized storage are owned by the storage.
+        let mut init = [PanickingClone(&counter, false), PanickingClone(&counter, false), PanickingClone(&counter, false)];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            BorrowOutSlice::<PanickingClone>::init_with_clone_from_slice(&mut init, &source);
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn init_from_iter_panic_drops_uninit() {
+        let counter = Cell::new(0);
+        let mut uninit = uninit_arr::<DropCounter, 4>();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let iter = (0..4).map(|i| if i < 2 { DropCounter(&counter) } else { panic!("iterator failed") });
+            BorrowOutSlice::<DropCounter>::init_from_iter(&mut uninit, iter);
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn init_from_iter_panic_keeps_init() {
+        let counter = Cell::new(0);
+        let mut init = [DropCounter(&counter), DropCounter(&counter), DropCounter(&counter)];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let iter = (0..3).map(|i| if i < 2 { DropCounter(&counter) } else { panic!("iterator failed") });
+            BorrowOutSlice::<DropCounter>::init_from_iter(&mut init, iter);
+        }));
+        assert!(result.is_err());
+        assert_eq!(counter.get(), 0);
+        drop(init);
+        assert_eq!(counter.get(), 3);
     }
 }
@@ -0,0 +1,182 @@
+use super::{BorrowOutSlice, Cursor};
+
+/// Cursor building a `str` in (possibly uninitialized) byte buffer.
+///
+/// This is a wrapper around `Cursor<u8, Arr>` that only allows writing valid
+/// UTF-8, so the written part can be accessed as `str`. It implements
+/// `core::fmt::Write`, which allows formatting into stack buffers without
+/// allocating.
+pub struct StrCursor<Arr: BorrowOutSlice<u8> + ?Sized> {
+    // Invariant: the written bytes are valid UTF-8.
+    cursor: Cursor<u8, Arr>,
+}
+
+impl<Arr: BorrowOutSlice<u8>> StrCursor<Arr> {
+    /// Creates `StrCursor` initialized with position 0
+    pub fn new(buf: Arr) -> Self {
+        StrCursor {
+            cursor: Cursor::new(buf),
+        }
+    }
+}
+
+impl<Arr: BorrowOutSlice<u8> + ?Sized> StrCursor<Arr> {
+    /// Appends the whole string at the end of the buffer.
+    ///
+    /// Returns the string back if it doesn't fit into the remaining part of
+    /// the buffer, leaving the buffer unchanged.
+    pub fn push_str<'s>(&mut self, s: &'s str) -> Result<&mut str, &'s str> {
+        if s.len() > self.cursor.remaining_count() {
+            return Err(s);
+        }
+        let written = self.cursor.push_iter(s.as_bytes());
+        // The bytes were copied from str.
+        unsafe {
+            Ok(core::str::from_utf8_unchecked_mut(written))
+        }
+    }
+
+    /// Appends the character at the end of the buffer.
+    ///
+    /// Returns the character back if it doesn't fit into the remaining part
+    /// of the buffer.
+    pub fn push(&mut self, c: char) -> Result<&mut str, char> {
+        let mut buf = [0; 4];
+        self.push_str(c.encode_utf8(&mut buf)).map_err(|_| c)
+    }
+
+    /// Returns the number of bytes that can be written to this cursor.
+    pub fn remaining_count(&self) -> usize {
+        self.cursor.remaining_count()
+    }
+
+    /// Resets the position to 0.
+    pub fn reset(&mut self) {
+        self.cursor.reset();
+    }
+
+    /// Access the written string.
+    pub fn as_str(&self) -> &str {
+        unsafe {
+            core::str::from_utf8_unchecked(self.cursor.written())
+        }
+    }
+
+    /// Mutably access the written string.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe {
+            core::str::from_utf8_unchecked_mut(self.cursor.written_mut())
+        }
+    }
+}
+
+impl<'a, Arr: BorrowOutSlice<u8> + ?Sized> StrCursor<&'a mut Arr> {
+    /// Converts the cursor into the written string, preserving the lifetime
+    /// of the storage.
+    pub fn into_str(self) -> &'a mut str {
+        unsafe {
+            core::str::from_utf8_unchecked_mut(self.cursor.into_written())
+        }
+    }
+}
+
+impl<Arr: BorrowOutSlice<u8>> From<Arr> for StrCursor<Arr> {
+    fn from(value: Arr) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Writes the whole string or nothing.
+///
+/// Returns `fmt::Error` if the string doesn't fit into the remaining part of
+/// the buffer.
+impl<Arr: BorrowOutSlice<u8> + ?Sized> core::fmt::Write for StrCursor<Arr> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.push_str(s).map(drop).map_err(|_| core::fmt::Error)
+    }
+
+    fn write_char(&mut self, c: char) -> core::fmt::Result {
+        self.push(c).map(drop).map_err(|_| core::fmt::Error)
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod alloc_impls {
+    use super::StrCursor;
+    use super::super::Cursor;
+    use core::mem::MaybeUninit;
+
+    use alloc::boxed::Box;
+    use alloc::string::String;
+
+    impl StrCursor<Box<[MaybeUninit<u8>]>> {
+        /// Constructs the `StrCursor` from `String`, preserving its contents
+        /// and using its spare capacity as the remaining part of the buffer.
+        pub fn from_string(string: String) -> Self {
+            StrCursor {
+                cursor: Cursor::from_vec_preserving_len(string.into_bytes()),
+            }
+        }
+
+        /// Converts the cursor back into `String` without reallocating.
+        pub fn into_string(self) -> String {
+            unsafe {
+                String::from_utf8_unchecked(self.cursor.into_vec())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::fmt::Write;
+    use core::mem::MaybeUninit;
+
+    #[test]
+    fn arr() {
+        let mut uninit = [MaybeUninit::uninit(); 8];
+        let mut cursor = StrCursor::new(&mut uninit);
+        assert_eq!(cursor.push_str("hello").expect("Array full"), "hello");
+        assert_eq!(cursor.push('č').expect("Array full"), "č");
+        assert_eq!(cursor.push('€'), Err('€'));
+        assert_eq!(cursor.push_str("world"), Err("world"));
+        assert_eq!(cursor.as_str(), "helloč");
+        assert_eq!(cursor.into_str(), "helloč");
+    }
+
+    #[test]
+    fn fmt_write() {
+        let mut uninit = [MaybeUninit::uninit(); 16];
+        let mut cursor = StrCursor::new(&mut uninit);
+        write!(cursor, "{} + {} = {}", 1, 2, 1 + 2).expect("Array full");
+        assert_eq!(cursor.as_str(), "1 + 2 = 3");
+        let suffix = "too long";
+        assert!(write!(cursor, "{}", suffix).is_err());
+        assert_eq!(cursor.as_str(), "1 + 2 = 3");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn boxed_slice() {
+        use alloc::boxed::Box;
+
+        let uninit: Box<[MaybeUninit<u8>]> = alloc::vec![MaybeUninit::uninit(); 4].into_boxed_slice();
+        let mut cursor = StrCursor::new(uninit);
+        cursor.push_str("abc").expect("Array full");
+        assert_eq!(cursor.as_str(), "abc");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn string() {
+        use alloc::string::String;
+
+        let mut string = String::with_capacity(16);
+        string.push_str("hello");
+        let mut cursor = StrCursor::from_string(string);
+        let world = "world";
+        write!(cursor, " {}", world).expect("Array full");
+        assert_eq!(cursor.into_string(), "hello world");
+    }
+}
@@ -0,0 +1,12 @@
+//! Helpers shared by the tests of multiple modules.
+
+use core::cell::Cell;
+
+/// Counts how many times it was dropped.
+pub(crate) struct DropCounter<'a>(pub(crate) &'a Cell<usize>);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
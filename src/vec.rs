@@ -0,0 +1,90 @@
+//! Helpers for using vectors.
+
+use alloc::vec::Vec;
+use crate::slice::OutSlice;
+
+/// Extension trait allowing initialization of the spare capacity of `Vec`.
+pub trait VecExt {
+    /// Type of the items stored in the vector.
+    type Item;
+
+    /// Returns the spare capacity of the vector as `OutSlice`.
+    ///
+    /// The items written into the returned slice are not part of the vector
+    /// until its length is set.
+    fn spare_out(&mut self) -> &mut OutSlice<Self::Item>;
+
+    /// Reserves space for at least `n` items, lets the closure initialize
+    /// them and appends the initialized items to the vector.
+    ///
+    /// The closure receives a slice of exactly `n` items and returns the
+    /// initialized prefix of the slice, which can be obtained by calling one
+    /// of the initialization methods of `OutSlice`. The number of initialized
+    /// items is returned from the method.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the closure returns a slice which is not a
+    /// prefix of the slice it received.
+    fn extend_with_out<F: FnOnce(&mut OutSlice<Self::Item>) -> &mut [Self::Item]>(&mut self, n: usize, f: F) -> usize;
+}
+
+impl<T> VecExt for Vec<T> {
+    type Item = T;
+
+    fn spare_out(&mut self) -> &mut OutSlice<Self::Item> {
+        self.spare_capacity_mut().into()
+    }
+
+    fn extend_with_out<F: FnOnce(&mut OutSlice<Self::Item>) -> &mut [Self::Item]>(&mut self, n: usize, f: F) -> usize {
+        self.reserve(n);
+        let out = &mut self.spare_out()[..n];
+        let ptr = out.as_mut_ptr();
+        let initialized = f(out);
+        // The closure can only get `&mut [T]` pointing to the spare capacity
+        // by initializing it. For zero-sized `T` every slice has the same
+        // dangling address, so the check can't tell them apart, but this is
+        // still fine because zero-sized values need no initialization.
+        assert!(initialized.as_mut_ptr() == ptr && initialized.len() <= n, "the closure must return the initialized prefix of the slice");
+        let initialized = initialized.len();
+        unsafe {
+            self.set_len(self.len() + initialized);
+        }
+        initialized
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn extend_with_out() {
+        let mut vec = vec![1u8, 2];
+        let initialized = vec.extend_with_out(3, |out| {
+            assert_eq!(out.len(), 3);
+            out[..2].copy_from_slice(&[3, 4])
+        });
+        assert_eq!(initialized, 2);
+        assert_eq!(vec, [1, 2, 3, 4]);
+        assert!(!vec.spare_out().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn extend_with_out_not_prefix() {
+        let mut vec = vec![1u8, 2];
+        vec.extend_with_out(3, |out| out[1..].copy_from_slice(&[3, 4]));
+    }
+
+    #[test]
+    fn extend_with_out_zst() {
+        let mut vec = vec![()];
+        assert_eq!(vec.extend_with_out(3, |out| out.fill(())), 3);
+        assert_eq!(vec.len(), 4);
+        let other = alloc::boxed::Box::leak(vec![(); 2].into_boxed_slice());
+        assert_eq!(vec.extend_with_out(3, move |_| other), 2);
+        assert_eq!(vec.len(), 6);
+    }
+}
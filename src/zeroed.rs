@@ -1,6 +1,15 @@
 //! Helpers for zeroing out the memory
 
-use core::mem::MaybeUninit;
+use core::cell::{Cell, UnsafeCell};
+use core::marker::PhantomData;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::num::{NonZeroUsize, NonZeroIsize, NonZeroU8, NonZeroI8, NonZeroU16, NonZeroI16, NonZeroU32, NonZeroI32, NonZeroU64, NonZeroI64, NonZeroU128, NonZeroI128, Wrapping};
+use core::ptr::NonNull;
+use core::sync::atomic;
+use crate::slice::BorrowOutSlice;
+
+#[cfg(feature = "derive")]
+pub use possibly_uninit_derive::ZeroValid;
 
 mod sealed {
     pub trait PtrCount {}
@@ -10,12 +19,104 @@ mod sealed {
     impl PtrCount for str {}
 }
 
+/// Marker trait declaring that all bit patterns are valid for `Self`.
+///
+/// This allows safely reinterpreting bytes as `Self` and vice versa. See
+/// `cast_slice`, `OutSlice::cast` and `Out::cast`.
+///
+/// # Safety
+///
+/// Implementing this trait for a type that has invalid bit patterns is
+/// undefined behavior. The type must not contain padding, uninitialized bytes
+/// or interior mutability either, since the bytes of `Self` may be observed
+/// as initialized `u8`s.
+pub unsafe trait AnyValid: ZeroValid {}
+
+unsafe impl AnyValid for usize {}
+unsafe impl AnyValid for isize {}
+unsafe impl AnyValid for u8 {}
+unsafe impl AnyValid for i8 {}
+unsafe impl AnyValid for u16 {}
+unsafe impl AnyValid for i16 {}
+unsafe impl AnyValid for u32 {}
+unsafe impl AnyValid for i32 {}
+unsafe impl AnyValid for u64 {}
+unsafe impl AnyValid for i64 {}
+unsafe impl AnyValid for u128 {}
+unsafe impl AnyValid for i128 {}
+unsafe impl AnyValid for f32 {}
+unsafe impl AnyValid for f64 {}
+
+unsafe impl<T: AnyValid> AnyValid for [T] {}
+unsafe impl<T: AnyValid, const N: usize> AnyValid for [T; N] {}
+unsafe impl<T: AnyValid> AnyValid for Wrapping<T> {}
+
 /// Marker trait declaring that all values of `<Self as PtrCount>::Item` are
 /// valid for `Self`.
-pub unsafe trait AnyValid: PtrCount {}
+///
+/// This used to be called `AnyValid`, before `AnyValid` started to mean that
+/// all bit patterns are valid.
+///
+/// # Safety
+///
+/// Implementing this trait for a type that has invalid bit patterns is
+/// undefined behavior.
+pub unsafe trait ItemsValid: PtrCount {}
+
+unsafe impl<T> ItemsValid for T {}
+unsafe impl<T> ItemsValid for [T] {}
 
-unsafe impl<T> AnyValid for T {}
-unsafe impl<T> AnyValid for [T] {}
+// `usize::is_multiple_of` and `<*const T>::is_aligned` are avoided in the
+// helpers below because they need a newer compiler.
+
+/// Checks that `ptr` is aligned for `T`.
+#[allow(clippy::manual_is_multiple_of)]
+pub(crate) fn is_aligned_for<T>(ptr: *const u8) -> bool {
+    ptr as usize % core::mem::align_of::<T>() == 0
+}
+
+/// Using `SAME_SIZE` fails to compile if `N` is not the size of `T`.
+///
+/// An associated constant is used instead of an inline `const` block, which
+/// needs a newer compiler.
+pub(crate) struct SizeCheck<T, const N: usize>(PhantomData<T>);
+
+impl<T, const N: usize> SizeCheck<T, N> {
+    pub(crate) const SAME_SIZE: () = assert!(N == core::mem::size_of::<T>(), "the size of the array must be equal to the size of T");
+}
+
+/// Using `SAME_LAYOUT` fails to compile if `A` and `B` differ in size or
+/// alignment.
+pub(crate) struct LayoutCheck<A, B>(PhantomData<(A, B)>);
+
+impl<A, B> LayoutCheck<A, B> {
+    pub(crate) const SAME_LAYOUT: () = assert!(
+        core::mem::size_of::<A>() == core::mem::size_of::<B>() && core::mem::align_of::<A>() == core::mem::align_of::<B>(),
+        "the types must have the same layout"
+    );
+}
+
+/// Checks that `len` bytes at `ptr` can be reinterpreted as a slice of `T`.
+#[allow(clippy::manual_is_multiple_of)]
+pub(crate) fn is_castable<T>(ptr: *const u8, len: usize) -> bool {
+    let size = core::mem::size_of::<T>();
+    size != 0 && len % size == 0 && is_aligned_for::<T>(ptr)
+}
+
+/// Reinterprets bytes as a slice of `T`.
+///
+/// Returns `None` if the bytes are not aligned for `T`, their length is not
+/// a multiple of the size of `T` or `T` is zero-sized.
+pub fn cast_slice<T: AnyValid>(bytes: &[u8]) -> Option<&[T]> {
+    let size = core::mem::size_of::<T>();
+    if !is_castable::<T>(bytes.as_ptr(), bytes.len()) {
+        return None;
+    }
+
+    unsafe {
+        Some(core::slice::from_raw_parts(bytes.as_ptr() as *const T, bytes.len() / size))
+    }
+}
 
 /// Trait unifying `T` and `[T]`
 ///
@@ -57,7 +158,7 @@ pub unsafe trait PtrCount: sealed::PtrCount {
     }
 
     /// Helper that turns the value into mutable slice based on `ptr` and `count`
-    fn as_slice_mut(&mut self) -> &mut [Self::Item] where Self: AnyValid {
+    fn as_slice_mut(&mut self) -> &mut [Self::Item] where Self: ItemsValid {
         unsafe {
             let (ptr, count) = self.ptr_count_mut();
             core::slice::from_raw_parts_mut(ptr, count)
@@ -104,6 +205,9 @@ unsafe impl PtrCount for str {
 /// Trait declaring that overwriting the whole memory location of `Self` with
 /// zeroes produces a valid value.
 ///
+/// With the `derive` feature, the trait can be implemented safely using
+/// `#[derive(ZeroValid)]`, which checks that all fields are `ZeroValid`.
+///
 /// # Safety
 ///
 /// The trait is unsafe because implementing it for a type that doesn't permit
@@ -123,6 +227,64 @@ pub unsafe trait ZeroValid {
             core::ptr::write_bytes(ptr, 0, count);
         }
     }
+
+    /// Overwrites value with zeroes in a way that can't be optimized out.
+    ///
+    /// Unlike `write_zeroes`, this uses volatile writes followed by a
+    /// compiler fence, so the compiler can't remove the writes even if the
+    /// value is dropped or deallocated right after. This is useful for wiping
+    /// secrets, such as keys. Note that it doesn't wipe copies of the value
+    /// the compiler might have made, e.g. when the value was moved.
+    fn secure_zero(&mut self) where Self: PtrCount {
+        unsafe {
+            let (ptr, count) = self.ptr_count_mut();
+            let ptr = ptr as *mut u8;
+            for i in 0..(count * core::mem::size_of::<Self::Item>()) {
+                core::ptr::write_volatile(ptr.add(i), 0);
+            }
+            atomic::compiler_fence(atomic::Ordering::SeqCst);
+        }
+    }
+}
+
+/// Wrapper securely wiping the storage when dropped.
+///
+/// The whole storage is overwritten with zeroes using `secure_zero`, even if
+/// it's `MaybeUninit` storage. In case of storage containing initialized
+/// items, the items are dropped by the storage after they were zeroed.
+pub struct Zeroizing<P: BorrowOutSlice<Item>, Item: ZeroValid = u8> {
+    _phantom: PhantomData<[Item]>,
+    storage: P,
+}
+
+impl<P: BorrowOutSlice<Item>, Item: ZeroValid> Zeroizing<P, Item> {
+    /// Wraps the storage.
+    pub fn new(storage: P) -> Self {
+        Zeroizing {
+            _phantom: PhantomData,
+            storage,
+        }
+    }
+}
+
+impl<P: BorrowOutSlice<Item>, Item: ZeroValid> core::ops::Deref for Zeroizing<P, Item> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        &self.storage
+    }
+}
+
+impl<P: BorrowOutSlice<Item>, Item: ZeroValid> core::ops::DerefMut for Zeroizing<P, Item> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.storage
+    }
+}
+
+impl<P: BorrowOutSlice<Item>, Item: ZeroValid> Drop for Zeroizing<P, Item> {
+    fn drop(&mut self) {
+        self.storage.borrow_out_slice().secure_zero();
+    }
 }
 
 /// Note that while `MaybeUninit<T>` may always contain zero bit pattern,
@@ -159,38 +321,7 @@ unsafe impl ZeroValid for f32 {}
 unsafe impl ZeroValid for f64 {}
 
 unsafe impl<T: ZeroValid> ZeroValid for [T] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 1] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 2] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 3] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 4] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 5] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 6] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 7] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 8] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 9] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 10] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 11] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 12] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 13] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 14] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 15] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 16] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 17] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 18] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 19] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 20] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 21] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 22] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 23] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 24] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 25] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 26] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 27] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 28] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 29] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 30] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 31] {}
-unsafe impl<T: ZeroValid> ZeroValid for [T; 32] {}
+unsafe impl<T: ZeroValid, const N: usize> ZeroValid for [T; N] {}
 
 unsafe impl<A: ZeroValid, B: ZeroValid, C: ZeroValid, D: ZeroValid, E: ZeroValid, F: ZeroValid, G: ZeroValid, H: ZeroValid> ZeroValid for (A, B, C, D, E, F, G, H) {}
 unsafe impl<A: ZeroValid, B: ZeroValid, C: ZeroValid, D: ZeroValid, E: ZeroValid, F: ZeroValid, G: ZeroValid> ZeroValid for (A, B, C, D, E, F, G) {}
@@ -201,3 +332,216 @@ unsafe impl<A: ZeroValid, B: ZeroValid, C: ZeroValid> ZeroValid for (A, B, C) {}
 unsafe impl<A: ZeroValid, B: ZeroValid> ZeroValid for (A, B) {}
 unsafe impl<A: ZeroValid> ZeroValid for (A,) {}
 unsafe impl ZeroValid for () {}
+
+// `None` is guaranteed to be represented as null pointer.
+//
+// The impls are intentionally limited to `T: Sized`. For wide pointers
+// (`&[T]`, `Box<dyn Trait>`, ...) the niche lives in the data pointer, but the
+// language only guarantees that all-zero bytes are `None` for thin pointers,
+// so zeroed metadata is not covered. See the "Representation" section of
+// `core::option`.
+unsafe impl<T> ZeroValid for Option<&T> {}
+unsafe impl<T> ZeroValid for Option<&mut T> {}
+unsafe impl<T> ZeroValid for Option<NonNull<T>> {}
+#[cfg(feature = "alloc")]
+unsafe impl<T> ZeroValid for Option<alloc::boxed::Box<T>> {}
+
+unsafe impl ZeroValid for Option<NonZeroUsize> {}
+unsafe impl ZeroValid for Option<NonZeroIsize> {}
+unsafe impl ZeroValid for Option<NonZeroU8> {}
+unsafe impl ZeroValid for Option<NonZeroI8> {}
+unsafe impl ZeroValid for Option<NonZeroU16> {}
+unsafe impl ZeroValid for Option<NonZeroI16> {}
+unsafe impl ZeroValid for Option<NonZeroU32> {}
+unsafe impl ZeroValid for Option<NonZeroI32> {}
+unsafe impl ZeroValid for Option<NonZeroU64> {}
+unsafe impl ZeroValid for Option<NonZeroI64> {}
+unsafe impl ZeroValid for Option<NonZeroU128> {}
+unsafe impl ZeroValid for Option<NonZeroI128> {}
+
+/// Implements `ZeroValid` for `Option` of function pointers with given
+/// arguments.
+///
+/// Note that this doesn't cover higher-ranked function pointers, such as
+/// `fn(&u8)`.
+macro_rules! impl_fn_ptr {
+    ($($arg:ident),*) => {
+        unsafe impl<Ret, $($arg),*> ZeroValid for Option<fn($($arg),*) -> Ret> {}
+        unsafe impl<Ret, $($arg),*> ZeroValid for Option<unsafe fn($($arg),*) -> Ret> {}
+        unsafe impl<Ret, $($arg),*> ZeroValid for Option<extern "C" fn($($arg),*) -> Ret> {}
+        unsafe impl<Ret, $($arg),*> ZeroValid for Option<unsafe extern "C" fn($($arg),*) -> Ret> {}
+    }
+}
+
+impl_fn_ptr!(A, B, C, D, E, F, G, H);
+impl_fn_ptr!(A, B, C, D, E, F, G);
+impl_fn_ptr!(A, B, C, D, E, F);
+impl_fn_ptr!(A, B, C, D, E);
+impl_fn_ptr!(A, B, C, D);
+impl_fn_ptr!(A, B, C);
+impl_fn_ptr!(A, B);
+impl_fn_ptr!(A);
+impl_fn_ptr!();
+
+#[cfg(target_has_atomic = "8")]
+unsafe impl ZeroValid for atomic::AtomicBool {}
+#[cfg(target_has_atomic = "8")]
+unsafe impl ZeroValid for atomic::AtomicU8 {}
+#[cfg(target_has_atomic = "8")]
+unsafe impl ZeroValid for atomic::AtomicI8 {}
+#[cfg(target_has_atomic = "16")]
+unsafe impl ZeroValid for atomic::AtomicU16 {}
+#[cfg(target_has_atomic = "16")]
+unsafe impl ZeroValid for atomic::AtomicI16 {}
+#[cfg(target_has_atomic = "32")]
+unsafe impl ZeroValid for atomic::AtomicU32 {}
+#[cfg(target_has_atomic = "32")]
+unsafe impl ZeroValid for atomic::AtomicI32 {}
+#[cfg(target_has_atomic = "64")]
+unsafe impl ZeroValid for atomic::AtomicU64 {}
+#[cfg(target_has_atomic = "64")]
+unsafe impl ZeroValid for atomic::AtomicI64 {}
+#[cfg(target_has_atomic = "ptr")]
+unsafe impl ZeroValid for atomic::AtomicUsize {}
+#[cfg(target_has_atomic = "ptr")]
+unsafe impl ZeroValid for atomic::AtomicIsize {}
+/// The pointer bit pattern zero is valid, but it doesn't mean that you can
+/// dereference it!
+#[cfg(target_has_atomic = "ptr")]
+unsafe impl<T> ZeroValid for atomic::AtomicPtr<T> {}
+
+unsafe impl<T: ZeroValid + ?Sized> ZeroValid for Cell<T> {}
+unsafe impl<T: ZeroValid + ?Sized> ZeroValid for UnsafeCell<T> {}
+unsafe impl<T: ZeroValid + ?Sized> ZeroValid for ManuallyDrop<T> {}
+unsafe impl<T: ZeroValid> ZeroValid for Wrapping<T> {}
+unsafe impl<T: ?Sized> ZeroValid for PhantomData<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn niche_none() {
+        assert!(<Option<&u8>>::zeroed().is_none());
+        assert!(<Option<NonNull<u8>>>::zeroed().is_none());
+        assert!(<Option<NonZeroU32>>::zeroed().is_none());
+        assert!(<Option<fn(u8) -> u8>>::zeroed().is_none());
+    }
+
+    #[test]
+    fn cast_slice() {
+        let words = [1u32, 2];
+        let bytes = unsafe { core::slice::from_raw_parts(words.as_ptr() as *const u8, 8) };
+        assert_eq!(super::cast_slice::<u32>(bytes), Some(&[1u32, 2][..]));
+        assert_eq!(super::cast_slice::<u32>(&bytes[..7]), None);
+        assert_eq!(super::cast_slice::<u32>(&bytes[1..5]), None);
+        assert_eq!(super::cast_slice::<[u8; 0]>(bytes), None);
+    }
+
+    #[test]
+    fn as_slice_mut_any_item() {
+        // `Option<&str>` has invalid bit patterns, so it's not `AnyValid`.
+        let mut value = [Some("foo"), None];
+        assert_eq!(value.as_slice_mut().len(), 1);
+        assert_eq!(value[..].as_slice_mut(), &[Some("foo"), None]);
+    }
+
+    #[test]
+    fn cast_out() {
+        let mut words = [MaybeUninit::<u32>::uninit(); 2];
+        let bytes = unsafe { core::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut MaybeUninit<u8>, 8) };
+        let out: &mut crate::slice::OutSlice<u8> = bytes.into();
+        assert!(out[1..5].cast::<u32>().is_none());
+        assert_eq!(out.cast::<u16>().expect("misaligned").fill(42), &[42; 4]);
+
+        let mut word = MaybeUninit::<u32>::uninit();
+        let out = crate::Out::from(unsafe { &mut *(word.as_mut_ptr() as *mut MaybeUninit<[u8; 4]>) });
+        assert_eq!(*out.cast::<i32>().unwrap_or_else(|_| panic!("misaligned")).write(-1), -1);
+    }
+
+    #[test]
+    fn secure_zero() {
+        let mut value = [42u32; 4];
+        value.secure_zero();
+        assert_eq!(value, [0; 4]);
+        let mut value = 42u64;
+        assert_eq!(*crate::Out::from(&mut value).secure_zero(), 0);
+        let mut uninit = [MaybeUninit::<u16>::uninit(); 4];
+        let out: &mut crate::slice::OutSlice<u16> = (&mut uninit[..]).into();
+        assert_eq!(out.secure_zero(), &[0; 4]);
+    }
+
+    #[test]
+    fn zeroizing() {
+        let mut key = [MaybeUninit::<u8>::uninit(); 16];
+        {
+            let mut storage: Zeroizing<_> = Zeroizing::new(&mut key);
+            storage.init_with_copy_from_slice(&[0xAA; 16]);
+        }
+        let bytes = unsafe { core::slice::from_raw_parts(key.as_ptr() as *const u8, key.len()) };
+        assert_eq!(bytes, &[0; 16]);
+    }
+
+    /// Storage that records whether its bytes were wiped right before the
+    /// heap allocation is freed.
+    #[cfg(feature = "alloc")]
+    struct CheckedBox<'a> {
+        bytes: alloc::boxed::Box<[u8]>,
+        wiped: &'a Cell<bool>,
+    }
+
+    #[cfg(feature = "alloc")]
+    impl core::ops::Deref for CheckedBox<'_> {
+        type Target = [u8];
+
+        fn deref(&self) -> &Self::Target {
+            &self.bytes
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl core::ops::DerefMut for CheckedBox<'_> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.bytes
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    unsafe impl crate::deref_markers::StableDeref for CheckedBox<'_> {}
+
+    #[cfg(feature = "alloc")]
+    impl Drop for CheckedBox<'_> {
+        fn drop(&mut self) {
+            // Volatile reads, so that the check can't be optimized based on
+            // the knowledge that the memory is about to be freed.
+            let ptr = self.bytes.as_ptr();
+            let wiped = (0..self.bytes.len()).all(|i| unsafe { core::ptr::read_volatile(ptr.add(i)) } == 0);
+            self.wiped.set(wiped);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn zeroizing_box() {
+        let wiped = Cell::new(false);
+        let storage = CheckedBox {
+            bytes: alloc::vec![0xAA; 32].into_boxed_slice(),
+            wiped: &wiped,
+        };
+        let mut key: Zeroizing<CheckedBox<'_>> = Zeroizing::new(storage);
+        key[0] = 0x55;
+        drop(key);
+        assert!(wiped.get());
+    }
+
+    #[test]
+    fn wrappers() {
+        assert_eq!(AtomicUsize::zeroed().load(Ordering::Relaxed), 0);
+        assert_eq!(Cell::<u32>::zeroed().get(), 0);
+        assert_eq!(Wrapping::<i8>::zeroed(), Wrapping(0));
+        let mut slots: [Option<NonNull<u8>>; 4] = [NonNull::dangling().into(); 4];
+        slots.write_zeroes();
+        assert!(slots.iter().all(Option::is_none));
+    }
+}